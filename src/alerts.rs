@@ -0,0 +1,183 @@
+use std::fmt;
+use std::io;
+use std::process::Command;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rules::Comparator;
+use crate::{ColumnKind, WatchItem};
+
+/// The shortest gap between two notifications from the same alert, so a price hovering around
+/// the threshold doesn't fire on every poll.
+const COOLDOWN: TimeDelta = TimeDelta::minutes(5);
+
+/// Notify when `field` of the `symbol` market starts comparing true against `value`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub symbol: String,
+    pub field: ColumnKind,
+    pub comparator: Comparator,
+    pub value: f64,
+}
+
+impl Alert {
+    /// Whether the alert's condition holds for `item`, `None` if it's another market or lacks
+    /// the field.
+    fn holds(&self, item: &WatchItem) -> Option<bool> {
+        if item.symbol.as_deref() != Some(self.symbol.as_str()) {
+            return None;
+        }
+        let field = self.field.number(item)?;
+
+        Some(self.comparator.holds(field, self.value))
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.symbol, self.field, self.comparator, self.value
+        )
+    }
+}
+
+/// What an alert last saw, kept alongside it for as long as the app runs.
+#[derive(Debug, Clone, Default)]
+pub struct AlertState {
+    /// Whether the condition held at the last fetch that had the market, unknown until then.
+    holding: Option<bool>,
+    last_fired: Option<DateTime<Utc>>,
+}
+
+/// Checks every alert against a fetch and returns the ones that just fired.
+///
+/// An alert fires when its condition goes from false to true, and at most once per `COOLDOWN`.
+/// The first fetch only records where things stand, so alerts that already hold stay quiet.
+pub fn check<'a>(
+    alerts: &'a [Alert],
+    states: &mut Vec<AlertState>,
+    items: &[WatchItem],
+    now: DateTime<Utc>,
+) -> Vec<&'a Alert> {
+    states.resize_with(alerts.len(), AlertState::default);
+
+    let mut fired = Vec::new();
+    for (alert, state) in alerts.iter().zip(states.iter_mut()) {
+        let Some(holds) = items.iter().find_map(|item| alert.holds(item)) else {
+            continue;
+        };
+        let crossed = holds && state.holding == Some(false);
+        let cooled_down = state.last_fired.is_none_or(|at| now - at >= COOLDOWN);
+        if crossed && cooled_down {
+            state.last_fired = Some(now);
+            fired.push(alert);
+        }
+        state.holding = Some(holds);
+    }
+
+    fired
+}
+
+/// Shows a desktop notification through the platform's own tool.
+pub fn notify(title: &str, body: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            &format!(
+                "display notification {} with title {}",
+                quote(body),
+                quote(title)
+            ),
+        ]);
+        command
+    } else if cfg!(target_os = "windows") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "desktop notifications aren't supported on Windows",
+        ));
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args([title, body]);
+        command
+    };
+
+    let mut child = command.spawn()?;
+    // Waiting off the UI thread reaps the tool once it exits instead of leaving a zombie.
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => log::warn!("Notification tool exited with {status}"),
+        Ok(_) => {}
+        Err(e) => log::warn!("Couldn't wait for the notification tool: {e}"),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert() -> Alert {
+        Alert {
+            symbol: "PF_XBTUSD".to_string(),
+            field: ColumnKind::MarkPrice,
+            comparator: Comparator::Greater,
+            value: 100.0,
+        }
+    }
+
+    fn price(mark_price: f64) -> Vec<WatchItem> {
+        vec![WatchItem {
+            symbol: Some("PF_XBTUSD".to_string()),
+            mark_price: Some(mark_price),
+            ..Default::default()
+        }]
+    }
+
+    fn start() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn fires_on_crossing_only() {
+        let alerts = [alert()];
+        let mut states = Vec::new();
+
+        assert!(check(&alerts, &mut states, &price(99.0), start()).is_empty());
+        assert_eq!(check(&alerts, &mut states, &price(101.0), start()).len(), 1);
+        // Staying above the threshold is not a new crossing.
+        assert!(check(&alerts, &mut states, &price(102.0), start()).is_empty());
+    }
+
+    #[test]
+    fn already_holding_on_first_fetch_stays_quiet() {
+        let alerts = [alert()];
+        let mut states = Vec::new();
+
+        assert!(check(&alerts, &mut states, &price(101.0), start()).is_empty());
+    }
+
+    #[test]
+    fn hovering_price_is_debounced() {
+        let alerts = [alert()];
+        let mut states = Vec::new();
+        let later = |minutes| start() + TimeDelta::minutes(minutes);
+
+        check(&alerts, &mut states, &price(99.0), later(0));
+        assert_eq!(
+            check(&alerts, &mut states, &price(101.0), later(0)).len(),
+            1
+        );
+        check(&alerts, &mut states, &price(99.0), later(1));
+        assert!(check(&alerts, &mut states, &price(101.0), later(2)).is_empty());
+        check(&alerts, &mut states, &price(99.0), later(5));
+        assert_eq!(
+            check(&alerts, &mut states, &price(101.0), later(6)).len(),
+            1
+        );
+    }
+}
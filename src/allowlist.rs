@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::WatchItem;
+
+/// Reads an allowlist file: one symbol per line, blank lines and `#` comments ignored.
+///
+/// Symbols are matched case-insensitively, so they are stored upper-cased.
+pub fn load(path: &Path) -> io::Result<HashSet<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_ascii_uppercase)
+        .collect())
+}
+
+pub fn contains(allowlist: &HashSet<String>, item: &WatchItem) -> bool {
+    item.symbol
+        .as_deref()
+        .is_some_and(|symbol| allowlist.contains(&symbol.to_ascii_uppercase()))
+}
+
+/// Allowlist entries that match none of `tickers`, sorted for display.
+pub fn unmatched<'a>(allowlist: &'a HashSet<String>, tickers: &[WatchItem]) -> Vec<&'a str> {
+    let symbols: HashSet<String> = tickers
+        .iter()
+        .filter_map(|item| item.symbol.as_deref())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let mut unmatched: Vec<&str> = allowlist
+        .iter()
+        .filter(|entry| !symbols.contains(*entry))
+        .map(String::as_str)
+        .collect();
+    unmatched.sort_unstable();
+
+    unmatched
+}
@@ -0,0 +1,135 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::theme::{ThemeChoice, ThemeMode};
+
+pub const USAGE: &str = "\
+usage: krader [--help] [--config-dir <dir>] [--endpoint <url>] [--interval <seconds>] [--theme <theme>]
+
+themes: system, dark, light, dracula, nord, solarized-light, solarized-dark";
+
+/// Settings given on the command line, each overriding the saved config for this run.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    /// `--help` or `-h` was given, asking for the usage instead of a run.
+    pub help: bool,
+    pub config_dir: Option<PathBuf>,
+    pub endpoint: Option<String>,
+    pub interval: Option<Duration>,
+    pub theme: Option<ThemeChoice>,
+}
+
+impl Args {
+    /// Reads `args`, without the program name. Flags take their value as `--flag value` or
+    /// `--flag=value`.
+    pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg
+                .into_string()
+                .map_err(|arg| format!("argument {arg:?} is not valid UTF-8"))?;
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(OsString::from(value))),
+                None => (arg, None),
+            };
+            if matches!(flag.as_str(), "--help" | "-h") {
+                parsed.help = true;
+                continue;
+            }
+            if !matches!(
+                flag.as_str(),
+                "--config-dir" | "--endpoint" | "--interval" | "--theme"
+            ) {
+                return Err(format!("unknown argument {flag}"));
+            }
+            let value = inline
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} needs a value"))?;
+
+            match flag.as_str() {
+                "--config-dir" => parsed.config_dir = Some(PathBuf::from(value)),
+                "--endpoint" => parsed.endpoint = Some(text(&flag, value)?),
+                "--interval" => parsed.interval = Some(interval(&text(&flag, value)?)?),
+                "--theme" => parsed.theme = Some(theme(&text(&flag, value)?)?),
+                _ => unreachable!("flags are checked above"),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn text(flag: &str, value: OsString) -> Result<String, String> {
+    value
+        .into_string()
+        .map_err(|value| format!("{flag} value {value:?} is not valid UTF-8"))
+}
+
+fn interval(value: &str) -> Result<Duration, String> {
+    match value.parse::<u64>() {
+        Ok(seconds) if seconds > 0 => Ok(Duration::from_secs(seconds)),
+        _ => Err(format!(
+            "--interval must be a whole number of seconds above zero, got {value:?}"
+        )),
+    }
+}
+
+fn theme(value: &str) -> Result<ThemeChoice, String> {
+    Ok(match value.to_lowercase().as_str() {
+        "system" => ThemeChoice::System,
+        "dark" => ThemeChoice::Explicit(ThemeMode::Dark),
+        "light" => ThemeChoice::Explicit(ThemeMode::Light),
+        "dracula" => ThemeChoice::Explicit(ThemeMode::Dracula),
+        "nord" => ThemeChoice::Explicit(ThemeMode::Nord),
+        "solarized-light" => ThemeChoice::Explicit(ThemeMode::SolarizedLight),
+        "solarized-dark" => ThemeChoice::Explicit(ThemeMode::SolarizedDark),
+        _ => return Err(format!("unknown --theme {value:?}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn both_value_forms() {
+        let args = parse(&[
+            "--interval",
+            "10",
+            "--theme=light",
+            "--endpoint",
+            "https://demo-futures.kraken.com",
+            "--config-dir=./cfg",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args,
+            Args {
+                help: false,
+                config_dir: Some(PathBuf::from("./cfg")),
+                endpoint: Some("https://demo-futures.kraken.com".to_string()),
+                interval: Some(Duration::from_secs(10)),
+                theme: Some(ThemeChoice::Explicit(ThemeMode::Light)),
+            }
+        );
+        assert_eq!(parse(&[]).unwrap(), Args::default());
+        assert!(parse(&["-h"]).unwrap().help);
+        assert!(parse(&["--interval", "10", "--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        assert!(parse(&["--interval", "0"]).is_err());
+        assert!(parse(&["--interval", "fast"]).is_err());
+        assert!(parse(&["--theme", "purple"]).is_err());
+        assert!(parse(&["--interval"]).is_err());
+        assert!(parse(&["--verbose", "1"]).is_err());
+    }
+}
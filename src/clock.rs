@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// The source of the current time, so time-based displays can be tested.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// How long ago `then` was, e.g. "just now", "42s ago" or "3m ago".
+pub fn relative_time(then: DateTime<Utc>, clock: &dyn Clock) -> String {
+    age(then, clock.now())
+}
+
+/// How long before `now` `then` was, worded like `relative_time`.
+pub fn age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - then).num_seconds();
+
+    match seconds {
+        ..5 => "just now".to_string(),
+        5..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// Whether more than `threshold` has passed since `then`.
+pub fn is_stale(then: DateTime<Utc>, threshold: Duration, clock: &dyn Clock) -> bool {
+    (clock.now() - then)
+        .to_std()
+        .is_ok_and(|elapsed| elapsed > threshold)
+}
+
+/// The time left until `due`, zero once it has passed.
+pub fn countdown(due: DateTime<Utc>, clock: &dyn Clock) -> Duration {
+    (due - clock.now()).to_std().unwrap_or_default()
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// A clock that only moves when told to.
+    pub struct MockClock {
+        now: Cell<DateTime<Utc>>,
+    }
+
+    impl MockClock {
+        pub fn new(now: DateTime<Utc>) -> Self {
+            Self {
+                now: Cell::new(now),
+            }
+        }
+
+        pub fn advance(&self, by: Duration) {
+            self.now.set(self.now.get() + by);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now.get()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockClock;
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn relative_time_as_the_clock_advances() {
+        let clock = MockClock::new(start());
+        assert_eq!(relative_time(start(), &clock), "just now");
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(relative_time(start(), &clock), "just now");
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(relative_time(start(), &clock), "5s ago");
+        clock.advance(Duration::from_secs(55));
+        assert_eq!(relative_time(start(), &clock), "1m ago");
+        clock.advance(Duration::from_secs(3540));
+        assert_eq!(relative_time(start(), &clock), "1h ago");
+        clock.advance(Duration::from_secs(23 * 3600));
+        assert_eq!(relative_time(start(), &clock), "1d ago");
+    }
+
+    #[test]
+    fn future_times_read_as_just_now() {
+        let clock = MockClock::new(start());
+        let later = start() + Duration::from_secs(30);
+        assert_eq!(relative_time(later, &clock), "just now");
+    }
+
+    #[test]
+    fn staleness_flips_after_the_threshold() {
+        let clock = MockClock::new(start());
+        let threshold = Duration::from_secs(15);
+
+        clock.advance(Duration::from_secs(15));
+        assert!(!is_stale(start(), threshold, &clock));
+        clock.advance(Duration::from_secs(1));
+        assert!(is_stale(start(), threshold, &clock));
+    }
+
+    #[test]
+    fn countdown_reaches_zero_and_stays_there() {
+        let clock = MockClock::new(start());
+        let due = start() + Duration::from_secs(5);
+
+        assert_eq!(countdown(due, &clock), Duration::from_secs(5));
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(countdown(due, &clock), Duration::from_secs(2));
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(countdown(due, &clock), Duration::ZERO);
+    }
+}
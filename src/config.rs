@@ -0,0 +1,341 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ColumnKind;
+use crate::alerts::Alert;
+use crate::format::{ChangeDisplay, NumberStyle, Precision, Separator};
+use crate::geometry::WindowGeometry;
+use crate::grouping::Grouping;
+use crate::row_action::RowAction;
+use crate::rules::Rule;
+use crate::sort::SortDirection;
+use crate::theme::{Density, ThemeChoice, ThemeMode};
+use crate::transform::Transform;
+use krader::data::{DEFAULT_TIMEOUT, PRODUCTION_ENDPOINT};
+use krader::source::DataSource;
+
+/// User settings persisted between runs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub monospace_numbers: bool,
+    pub highlight_crossed: bool,
+    /// Round volume and open interest cells to grouped whole numbers.
+    pub whole_volumes: bool,
+    /// Shorten volume and open interest figures to K/M/B/T, overriding `whole_volumes`.
+    pub abbreviate_volumes: bool,
+    /// Fraction digits kept when abbreviating, e.g. 2 for `1.23M`.
+    pub abbrev_decimals: usize,
+    /// Per-column number styles, overriding the two volume settings above.
+    pub number_styles: HashMap<ColumnKind, NumberStyle>,
+    pub thousands_separator: Separator,
+    pub change_display: ChangeDisplay,
+    /// Shade bid and ask sizes by how large they are among the displayed markets.
+    pub color_sizes: bool,
+    /// Show bid, ask and their sizes in one column instead of four.
+    pub merge_quote: bool,
+    /// Name the market that moved most since its 24h open above the table.
+    pub show_mover_banner: bool,
+    /// Show the full-precision value behind a numeric cell on hover.
+    pub cell_tooltips: bool,
+    /// Shade every other row so a line is easy to follow across a wide table.
+    pub zebra_stripes: bool,
+    pub density: Density,
+    /// Use the OS light/dark preference instead of `theme`.
+    pub follow_system_theme: bool,
+    /// The theme explicitly chosen by the user, remembered while following the OS.
+    pub theme: ThemeMode,
+    pub grouping: Grouping,
+    /// The base URL of the Kraken Futures API, e.g. `https://demo-futures.kraken.com` for the
+    /// demo environment. `--endpoint` overrides it for one run.
+    pub endpoint: String,
+    /// How often tickers are polled. `--interval` overrides it for one run.
+    pub refresh_interval: Duration,
+    /// Space around the whole window content, in logical pixels.
+    pub outer_padding: u16,
+    /// Center the content in the window rather than anchoring it to the top left.
+    pub center_content: bool,
+    /// Keep the first column in place while the others scroll horizontally.
+    pub pin_first_column: bool,
+    /// Let column edges be dragged to resize them.
+    pub resizable_columns: bool,
+    /// Show the totals row under the table.
+    pub show_footer: bool,
+    /// Stretch the table to at least the window's width.
+    pub fill_width: bool,
+    /// How long a request may take before it fails as timed out.
+    pub request_timeout: Duration,
+    /// How many times the very first fetch is tried before showing the offline screen.
+    pub first_load_attempts: u32,
+    /// Hide every market not listed in the file at `allowlist_path`.
+    pub allowlist_only: bool,
+    /// A file of tradable symbols, one per line.
+    pub allowlist_path: String,
+    pub row_click_action: RowAction,
+    pub row_double_click_action: RowAction,
+    /// Numeric columns whose cells briefly highlight when their value changes.
+    pub flash_fields: Vec<ColumnKind>,
+    /// Symbols of the starred markets, which are listed above the rest.
+    pub favorites: HashSet<String>,
+    /// Columns switched off in the column picker.
+    pub hidden_columns: HashSet<ColumnKind>,
+    /// Whether to show Kraken's futures or spot markets.
+    pub data_source: DataSource,
+    /// Stream futures tickers over Kraken's WebSocket feed, polling only for snapshots.
+    pub live_feed: bool,
+    /// Follow at most this many markets over the live feed, favorites and then the displayed rows
+    /// first. The rest are polled.
+    pub max_live_markets: usize,
+    /// Highlight this many of the biggest gainers and losers by 24h change.
+    pub highlight_movers: Option<usize>,
+    /// Spread cells turn red when the spread is above this fraction of the mark price.
+    pub wide_spread_threshold: f64,
+    /// The sort column and direction to open with, the feed's order when unset.
+    pub sort: Option<(ColumnKind, SortDirection)>,
+    /// Column order and widths as last arranged; columns missing here use the default layout.
+    pub columns: Vec<ColumnLayout>,
+    /// Decimals of numeric columns without an entry in `precision`.
+    pub decimals: Precision,
+    /// Decimals of each price column, overriding `decimals`.
+    pub precision: HashMap<ColumnKind, Precision>,
+    /// The precisions last used with each data source, restored when switching back to it.
+    pub precision_presets: HashMap<DataSource, PrecisionPreset>,
+    /// Per-column cell transforms; columns without an entry display raw values.
+    pub transforms: HashMap<ColumnKind, Transform>,
+    /// Conditional formatting rules, applied in order with the last match winning.
+    pub rules: Vec<Rule>,
+    /// Markets to raise a desktop notification for when they cross a threshold.
+    pub alerts: Vec<Alert>,
+    /// Where the window was last left, the platform default when unset.
+    pub window: Option<WindowGeometry>,
+    /// Settings given on the command line for this run only, never saved.
+    #[serde(skip)]
+    pub launch: LaunchOverrides,
+}
+
+/// Command-line settings that win over the saved ones until the user changes them in the app.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LaunchOverrides {
+    pub refresh_interval: Option<Duration>,
+    pub theme: Option<ThemeChoice>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            monospace_numbers: false,
+            highlight_crossed: true,
+            whole_volumes: false,
+            abbreviate_volumes: true,
+            abbrev_decimals: 1,
+            change_display: ChangeDisplay::default(),
+            color_sizes: false,
+            merge_quote: false,
+            show_mover_banner: true,
+            cell_tooltips: true,
+            zebra_stripes: true,
+            density: Density::Normal,
+            follow_system_theme: true,
+            theme: ThemeMode::default(),
+            grouping: Grouping::default(),
+            endpoint: PRODUCTION_ENDPOINT.to_string(),
+            request_timeout: DEFAULT_TIMEOUT,
+            refresh_interval: Duration::from_secs(5),
+            outer_padding: 20,
+            center_content: true,
+            pin_first_column: false,
+            resizable_columns: true,
+            show_footer: true,
+            fill_width: true,
+            first_load_attempts: 5,
+            allowlist_only: false,
+            allowlist_path: String::new(),
+            row_click_action: RowAction::OpenDetail,
+            row_double_click_action: RowAction::CopySymbol,
+            flash_fields: vec![ColumnKind::MarkPrice, ColumnKind::Last],
+            favorites: HashSet::new(),
+            hidden_columns: HashSet::from([ColumnKind::FundingRateAnnualized]),
+            sort: None,
+            highlight_movers: None,
+            wide_spread_threshold: 0.005,
+            data_source: DataSource::Futures,
+            live_feed: true,
+            max_live_markets: 100,
+            columns: Vec::new(),
+            decimals: Precision::Auto,
+            precision: HashMap::new(),
+            precision_presets: HashMap::new(),
+            number_styles: HashMap::new(),
+            thousands_separator: Separator::Comma,
+            transforms: HashMap::new(),
+            rules: Vec::new(),
+            alerts: Vec::new(),
+            window: None,
+            launch: LaunchOverrides::default(),
+        }
+    }
+}
+
+/// A column's place in the saved layout.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ColumnLayout {
+    pub kind: ColumnKind,
+    pub width: f32,
+}
+
+/// The precision settings kept for one data source.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PrecisionPreset {
+    pub decimals: Precision,
+    pub precision: HashMap<ColumnKind, Precision>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("No config directory available on this platform")]
+    NoConfigDir,
+
+    #[error("Failed to access config file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to serialize config: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl Config {
+    /// Loads the config file, falling back to defaults when it is missing or unreadable.
+    pub fn load() -> Self {
+        let Some(path) = path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid config {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// How often tickers are polled this run.
+    pub fn refresh_interval(&self) -> Duration {
+        self.launch
+            .refresh_interval
+            .unwrap_or(self.refresh_interval)
+    }
+
+    /// The theme in use this run.
+    pub fn theme_choice(&self) -> ThemeChoice {
+        self.launch.theme.unwrap_or(if self.follow_system_theme {
+            ThemeChoice::System
+        } else {
+            ThemeChoice::Explicit(self.theme)
+        })
+    }
+
+    pub fn transform(&self, kind: ColumnKind) -> Transform {
+        self.transforms.get(&kind).cloned().unwrap_or_default()
+    }
+
+    pub fn precision(&self, kind: ColumnKind) -> Precision {
+        self.precision.get(&kind).copied().unwrap_or(self.decimals)
+    }
+
+    /// Switches to `source`, keeping the current precisions as the old source's preset and
+    /// restoring the new source's. Without a preset the current precisions carry over.
+    pub fn switch_data_source(&mut self, source: DataSource) {
+        let current = PrecisionPreset {
+            decimals: self.decimals,
+            precision: self.precision.clone(),
+        };
+        self.precision_presets.insert(self.data_source, current);
+        if let Some(preset) = self.precision_presets.get(&source) {
+            self.decimals = preset.decimals;
+            self.precision = preset.precision.clone();
+        }
+        self.data_source = source;
+    }
+
+    /// The column's own number style, or else what the volume settings ask for.
+    pub fn number_style(&self, kind: ColumnKind) -> NumberStyle {
+        if let Some(style) = self.number_styles.get(&kind) {
+            return *style;
+        }
+        let volume = matches!(
+            kind,
+            ColumnKind::Vol24h | ColumnKind::VolumeQuote | ColumnKind::OpenInterest
+        );
+        if volume && self.abbreviate_volumes {
+            NumberStyle::Abbreviated
+        } else if volume && self.whole_volumes {
+            NumberStyle::Grouped
+        } else {
+            NumberStyle::Plain
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = path().ok_or(ConfigError::NoConfigDir)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// Environment variable overriding the config directory, below the `--config-dir` flag.
+pub const DIR_ENV: &str = "KRADER_CONFIG_DIR";
+
+static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Picks the directory every persisted file lives in: `flag`, then `KRADER_CONFIG_DIR`,
+/// then the platform config directory.
+///
+/// An override that can't be written to is reported and skipped. Only the first call has any
+/// effect; later ones keep the directory already chosen.
+pub fn init_dir(flag: Option<PathBuf>) {
+    DIR.get_or_init(|| {
+        let env = std::env::var_os(DIR_ENV).map(PathBuf::from);
+        for (source, dir) in [("--config-dir", flag), (DIR_ENV, env)] {
+            let Some(dir) = dir else {
+                continue;
+            };
+            match check_writable(&dir) {
+                Ok(()) => return Some(dir),
+                Err(e) => log::warn!(
+                    "{source} {} is not writable ({e}), falling back to the default",
+                    dir.display()
+                ),
+            }
+        }
+
+        dirs::config_dir().map(|dir| dir.join("krader"))
+    });
+}
+
+/// The directory persisted files are kept in, `None` if the platform has no config directory.
+pub fn dir() -> Option<&'static Path> {
+    DIR.get_or_init(|| dirs::config_dir().map(|dir| dir.join("krader")))
+        .as_deref()
+}
+
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(".krader-write-test");
+    fs::write(&probe, b"")?;
+    fs::remove_file(probe)
+}
+
+fn path() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("config.json"))
+}
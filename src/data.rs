@@ -0,0 +1,672 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use crate::source::{DataSource, parse_spot};
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("HTTP request failed: {0}")]
+    Http(reqwest::Error),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Failed to parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Price field missing in response")]
+    MissingField,
+
+    #[error("Tickers array missing in response")]
+    MissingTickers,
+
+    #[error("No markets returned")]
+    NoMarkets,
+
+    #[error("Kraken returned an error: {0}")]
+    Api(String),
+
+    #[error("Rate limited by Kraken, polling again in {}s", retry_after.as_secs())]
+    RateLimited { retry_after: Duration },
+
+    #[error("Failed to parse price string: {0}")]
+    Parse(#[from] std::num::ParseFloatError),
+}
+
+/// Reads a figure sent either as a JSON number or as a numeric string, since Kraken uses both.
+/// Null, an empty string and anything else that isn't a number read as missing, so one odd
+/// field can't fail the whole response.
+pub(crate) fn lenient_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(f64),
+        Text(String),
+        Other(serde::de::IgnoredAny),
+    }
+
+    Ok(match Option::<Raw>::deserialize(deserializer)? {
+        Some(Raw::Number(number)) => Some(number),
+        Some(Raw::Text(text)) => text.trim().parse().ok(),
+        Some(Raw::Other(_)) | None => None,
+    })
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            FetchError::Timeout
+        } else {
+            FetchError::Http(e)
+        }
+    }
+}
+
+/// One market's ticker, with every figure optional since Kraken omits or nulls many of them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchItem {
+    pub symbol: Option<String>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub last: Option<f64>,
+    pub last_time: Option<String>,
+    pub tag: Option<String>,
+    pub pair: Option<String>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub mark_price: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub bid: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub bid_size: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub ask: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub ask_size: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub vol24h: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub volume_quote: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub open_interest: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub open24h: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub high24h: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub low24h: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub last_size: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub funding_rate: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub funding_rate_prediction: Option<f64>,
+    pub suspended: Option<bool>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub index_price: Option<f64>,
+    pub post_only: Option<bool>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub change24h: Option<f64>,
+}
+
+impl WatchItem {
+    /// Takes every field `update` has, keeping the rest. Live updates leave out some of what a
+    /// snapshot carries, like the last trade time.
+    pub fn merge(&mut self, update: WatchItem) {
+        // Destructured so a new field can't be forgotten here.
+        let WatchItem {
+            symbol,
+            last,
+            last_time,
+            tag,
+            pair,
+            mark_price,
+            bid,
+            bid_size,
+            ask,
+            ask_size,
+            vol24h,
+            volume_quote,
+            open_interest,
+            open24h,
+            high24h,
+            low24h,
+            last_size,
+            funding_rate,
+            funding_rate_prediction,
+            suspended,
+            index_price,
+            post_only,
+            change24h,
+        } = update;
+        fn take<T>(field: &mut Option<T>, update: Option<T>) {
+            if update.is_some() {
+                *field = update;
+            }
+        }
+
+        take(&mut self.symbol, symbol);
+        take(&mut self.last, last);
+        take(&mut self.last_time, last_time);
+        take(&mut self.tag, tag);
+        take(&mut self.pair, pair);
+        take(&mut self.mark_price, mark_price);
+        take(&mut self.bid, bid);
+        take(&mut self.bid_size, bid_size);
+        take(&mut self.ask, ask);
+        take(&mut self.ask_size, ask_size);
+        take(&mut self.vol24h, vol24h);
+        take(&mut self.volume_quote, volume_quote);
+        take(&mut self.open_interest, open_interest);
+        take(&mut self.open24h, open24h);
+        take(&mut self.high24h, high24h);
+        take(&mut self.low24h, low24h);
+        take(&mut self.last_size, last_size);
+        take(&mut self.funding_rate, funding_rate);
+        take(&mut self.funding_rate_prediction, funding_rate_prediction);
+        take(&mut self.suspended, suspended);
+        take(&mut self.index_price, index_price);
+        take(&mut self.post_only, post_only);
+        take(&mut self.change24h, change24h);
+    }
+
+    /// Splits a Kraken `BASE:QUOTE` pair into its two currencies.
+    pub fn base_quote(&self) -> Option<(&str, &str)> {
+        self.pair.as_deref()?.split_once(':')
+    }
+
+    /// `last_time` parsed from Kraken's RFC 3339 timestamps, like `2024-05-01T12:00:00.123Z`.
+    pub fn last_traded_at(&self) -> Option<DateTime<Utc>> {
+        let parsed = DateTime::parse_from_rfc3339(self.last_time.as_deref()?.trim()).ok()?;
+
+        Some(parsed.with_timezone(&Utc))
+    }
+
+    /// The bid-ask spread in price units.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask? - self.bid?)
+    }
+
+    /// The bid-ask spread as a fraction of the mark price.
+    pub fn relative_spread(&self) -> Option<f64> {
+        let (spread, mark_price) = (self.spread()?, self.mark_price?);
+
+        (mark_price != 0.0).then(|| spread / mark_price)
+    }
+
+    /// The bid-ask spread relative to the mark price, in basis points.
+    pub fn spread_bps(&self) -> Option<f64> {
+        Some(self.relative_spread()? * 10_000.0)
+    }
+
+    /// Whether the relative spread is above `threshold`, a fraction of the mark price. Markets
+    /// without a quote or mark price never are.
+    pub fn has_wide_spread(&self, threshold: f64) -> bool {
+        self.relative_spread()
+            .is_some_and(|spread| spread > threshold)
+    }
+
+    /// The mark price's premium over the index price, as a fraction of the index price.
+    pub fn premium(&self) -> Option<f64> {
+        let (mark_price, index_price) = (self.mark_price?, self.index_price?);
+
+        (index_price != 0.0).then(|| (mark_price - index_price) / index_price)
+    }
+
+    /// How far `last` has moved from `open24h`, in price units.
+    pub fn change_absolute(&self) -> Option<f64> {
+        Some(self.last? - self.open24h?)
+    }
+
+    /// How far `last` has moved from `open24h`, in percent.
+    pub fn change_since_open(&self) -> Option<f64> {
+        let (last, open) = (self.last?, self.open24h?);
+
+        (open != 0.0).then(|| (last - open) / open * 100.0)
+    }
+
+    /// Whether the book is locked or crossed, i.e. the bid is at or above the ask.
+    pub fn is_crossed(&self) -> bool {
+        matches!((self.bid, self.ask), (Some(bid), Some(ask)) if bid >= ask)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TickersResponse {
+    /// `"success"`, or `"error"` with the reason in `error`.
+    result: Option<String>,
+    error: Option<ApiError>,
+    tickers: Option<Vec<WatchItem>>,
+}
+
+/// The reason a request failed, which the docs give as a string and some responses as a list.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum ApiError {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ApiError {
+    fn message(self) -> String {
+        match self {
+            ApiError::One(error) => error,
+            ApiError::Many(errors) => errors.join(", "),
+        }
+    }
+}
+
+/// The live Kraken Futures API, used when no other endpoint is set or the one set is invalid.
+pub const PRODUCTION_ENDPOINT: &str = "https://futures.kraken.com";
+pub const TICKERS_PATH: &str = "/derivatives/api/v3/tickers";
+
+/// The tickers URL under `endpoint`, falling back to production with a warning if `endpoint`
+/// isn't an http(s) URL.
+pub fn tickers_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    match reqwest::Url::parse(endpoint) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => {
+            format!("{endpoint}{TICKERS_PATH}")
+        }
+        _ => {
+            log::warn!("Endpoint {endpoint:?} is not an http(s) URL, using {PRODUCTION_ENDPOINT}");
+            format!("{PRODUCTION_ENDPOINT}{TICKERS_PATH}")
+        }
+    }
+}
+
+/// How long a request may take by default before it fails, so a stalled connection can't hang
+/// polling.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The HTTP client shared by every fetch, so connections are pooled across polls. Requests
+/// taking longer than `timeout` fail with `FetchError::Timeout`.
+pub fn http_client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("krader/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("HTTP client settings are valid")
+}
+
+/// How long to back off after a 429 that doesn't say, or says in a form we don't read.
+pub(crate) const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Pauses before each retry of a failed request; one more attempt than there are delays is made.
+const FETCH_RETRY_DELAYS: [Duration; 2] = [Duration::from_millis(250), Duration::from_millis(500)];
+
+/// Fetches and parses the futures tickers of the API at `base_url`, like
+/// [`PRODUCTION_ENDPOINT`].
+pub async fn fetch_tickers(client: &Client, base_url: &str) -> Result<Vec<WatchItem>, FetchError> {
+    fetch(client, &tickers_url(base_url), DataSource::Futures).await
+}
+
+/// Fetches and parses `source` tickers from `url`, retrying network and HTTP status errors.
+///
+/// A body that doesn't parse won't fix itself and hammering a rate limit only prolongs it, so
+/// those fail straight away. So do timeouts, as a retry would stall just as long again.
+pub async fn fetch(
+    client: &Client,
+    url: &str,
+    source: DataSource,
+) -> Result<Vec<WatchItem>, FetchError> {
+    let mut delays = FETCH_RETRY_DELAYS.into_iter();
+    loop {
+        let started = Instant::now();
+        match get_body(client, url).await {
+            Ok(body) => {
+                log::debug!(
+                    "Fetched {url} in {:?}, {} bytes",
+                    started.elapsed(),
+                    body.len()
+                );
+                return match source {
+                    DataSource::Futures => parse_tickers(&body),
+                    DataSource::Spot => parse_spot(&body),
+                };
+            }
+            Err(FetchError::Http(e)) => match delays.next() {
+                Some(delay) => {
+                    log::info!("Fetching {url} failed, retrying in {delay:?}: {e}");
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(FetchError::Http(e)),
+            },
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn get_body(client: &Client, url: &str) -> Result<String, FetchError> {
+    let response = client.get(url).send().await?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        // Retry-After may also be an HTTP date, which Kraken doesn't send.
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+            .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs);
+        return Err(FetchError::RateLimited { retry_after });
+    }
+
+    Ok(response.error_for_status()?.text().await?)
+}
+
+/// Extracts the tickers from a response body. An error envelope, or an absent, null or empty
+/// array, is an error.
+pub fn parse_tickers(body: &str) -> Result<Vec<WatchItem>, FetchError> {
+    let resp: TickersResponse = serde_json::from_str(body)?;
+    if resp.result.as_deref() == Some("error") || resp.error.is_some() {
+        let message = resp
+            .error
+            .map_or("unknown error".to_string(), ApiError::message);
+        if message == "apiLimitExceeded" {
+            return Err(FetchError::RateLimited {
+                retry_after: DEFAULT_RETRY_AFTER,
+            });
+        }
+        return Err(FetchError::Api(message));
+    }
+
+    // An empty list is never a real market state, so keep what is on screen instead.
+    match resp.tickers {
+        None => Err(FetchError::MissingTickers),
+        Some(tickers) if tickers.is_empty() => Err(FetchError::NoMarkets),
+        Some(tickers) => Ok(tickers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(bid: Option<f64>, ask: Option<f64>) -> WatchItem {
+        WatchItem {
+            bid,
+            ask,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn crossed_detection() {
+        assert!(!quote(Some(99.0), Some(100.0)).is_crossed());
+        assert!(quote(Some(100.0), Some(100.0)).is_crossed());
+        assert!(quote(Some(101.0), Some(100.0)).is_crossed());
+        assert!(!quote(None, Some(100.0)).is_crossed());
+        assert!(!quote(Some(100.0), None).is_crossed());
+        assert!(!quote(None, None).is_crossed());
+    }
+
+    #[test]
+    fn wide_spread_classification() {
+        let book = |bid, ask, mark_price| WatchItem {
+            bid,
+            ask,
+            mark_price,
+            ..Default::default()
+        };
+
+        assert!(book(Some(99.0), Some(100.0), Some(100.0)).has_wide_spread(0.005));
+        assert!(!book(Some(99.6), Some(100.0), Some(100.0)).has_wide_spread(0.005));
+        // Exactly at the threshold isn't above it.
+        assert!(!book(Some(99.5), Some(100.0), Some(100.0)).has_wide_spread(0.005));
+        assert!(!book(Some(99.0), Some(100.0), Some(0.0)).has_wide_spread(0.005));
+        assert!(!book(Some(99.0), Some(100.0), None).has_wide_spread(0.005));
+        assert!(!book(None, Some(100.0), Some(100.0)).has_wide_spread(0.005));
+    }
+
+    #[test]
+    fn merging_keeps_fields_the_update_lacks() {
+        let mut item = WatchItem {
+            symbol: Some("PF_XBTUSD".to_string()),
+            last: Some(100.0),
+            last_time: Some("2026-01-01T12:00:00Z".to_string()),
+            bid: Some(99.0),
+            ..Default::default()
+        };
+        item.merge(WatchItem {
+            symbol: Some("PF_XBTUSD".to_string()),
+            last: Some(101.0),
+            ask: Some(102.0),
+            ..Default::default()
+        });
+
+        assert_eq!(item.last, Some(101.0));
+        assert_eq!(item.ask, Some(102.0));
+        assert_eq!(item.bid, Some(99.0));
+        assert_eq!(item.last_time.as_deref(), Some("2026-01-01T12:00:00Z"));
+    }
+
+    #[test]
+    fn premium_over_index() {
+        let prices = |mark_price, index_price| WatchItem {
+            mark_price,
+            index_price,
+            ..Default::default()
+        };
+
+        assert_eq!(prices(Some(101.0), Some(100.0)).premium(), Some(0.01));
+        assert_eq!(prices(Some(99.0), Some(100.0)).premium(), Some(-0.01));
+        assert_eq!(prices(Some(100.0), Some(100.0)).premium(), Some(0.0));
+        assert_eq!(prices(Some(100.0), Some(0.0)).premium(), None);
+        assert_eq!(prices(None, Some(100.0)).premium(), None);
+        assert_eq!(prices(Some(100.0), None).premium(), None);
+    }
+
+    /// Serves one canned HTTP response per connection, in order, and returns its base URL.
+    async fn serve(responses: Vec<String>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let body = r#"{"result":"success","tickers":[{"symbol":"PF_XBTUSD"}]}"#;
+        let ok = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let url = serve(vec![
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            ok,
+        ])
+        .await;
+
+        let tickers = fetch_tickers(&Client::new(), &url).await.unwrap();
+        assert_eq!(tickers.len(), 1);
+        assert_eq!(tickers[0].symbol.as_deref(), Some("PF_XBTUSD"));
+    }
+
+    #[tokio::test]
+    async fn rate_limits_are_not_retried() {
+        let url = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 7\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        ])
+        .await;
+
+        assert!(matches!(
+            fetch_tickers(&Client::new(), &url).await,
+            Err(FetchError::RateLimited { retry_after }) if retry_after == Duration::from_secs(7)
+        ));
+    }
+
+    #[tokio::test]
+    async fn hung_requests_time_out() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        // Accepts the connection and never answers.
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let client = http_client(Duration::from_millis(100));
+        assert!(matches!(
+            fetch_tickers(&client, &url).await,
+            Err(FetchError::Timeout)
+        ));
+    }
+
+    /// Trimmed from a real `/tickers` response: a perpetual, a suspended fixed-maturity future
+    /// and a spot index, which only carries a handful of fields.
+    const CAPTURED: &str = r#"{
+        "result": "success",
+        "tickers": [
+            {
+                "tag": "perpetual", "pair": "XBT:USD", "symbol": "PF_XBTUSD",
+                "markPrice": 104215.5, "bid": 104210, "bidSize": 0.8421,
+                "ask": 104221, "askSize": 1.2035, "vol24h": 1893.4412,
+                "volumeQuote": 196885213.27, "openInterest": 1521.9035,
+                "open24h": 102980, "high24h": 105120, "low24h": 102511,
+                "lastTime": "2025-05-12T09:29:58.412Z", "last": 104218, "lastSize": 0.0125,
+                "suspended": false, "fundingRate": 0.0000124,
+                "fundingRatePrediction": 0.0000131, "postOnly": false,
+                "indexPrice": 104190.2, "change24h": 1.2,
+                "isUnderlyingMarketClosed": false
+            },
+            {
+                "tag": "month", "pair": "XBT:USD", "symbol": "FF_XBTUSD_250530",
+                "markPrice": 104980, "bid": null, "ask": null,
+                "suspended": true, "postOnly": false
+            },
+            {
+                "symbol": "in_xbtusd", "last": 104190.2,
+                "lastTime": "2025-05-12T09:29:59.000Z"
+            }
+        ],
+        "serverTime": "2025-05-12T09:30:00.000Z"
+    }"#;
+
+    #[test]
+    fn captured_response_parses() {
+        let tickers = parse_tickers(CAPTURED).unwrap();
+        assert_eq!(tickers.len(), 3);
+
+        let perpetual = &tickers[0];
+        assert_eq!(perpetual.symbol.as_deref(), Some("PF_XBTUSD"));
+        assert_eq!(perpetual.mark_price, Some(104215.5));
+        assert_eq!(perpetual.bid, Some(104210.0));
+        assert_eq!(perpetual.funding_rate, Some(0.0000124));
+        assert_eq!(perpetual.suspended, Some(false));
+
+        let future = &tickers[1];
+        assert_eq!(future.suspended, Some(true));
+        assert_eq!(future.bid, None);
+        assert_eq!(future.ask, None);
+        assert_eq!(future.funding_rate, None);
+
+        let index = &tickers[2];
+        assert_eq!(index.symbol.as_deref(), Some("in_xbtusd"));
+        assert_eq!(index.last, Some(104190.2));
+        assert_eq!(index.mark_price, None);
+        assert_eq!(index.pair, None);
+        assert_eq!(index.suspended, None);
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        let tickers = parse_tickers(
+            r#"{"tickers":[{"symbol":"PF_XBTUSD","premium":0.02,"extra":{"nested":[1,2]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(tickers[0].symbol.as_deref(), Some("PF_XBTUSD"));
+    }
+
+    #[test]
+    fn numbers_may_be_strings() {
+        let last = |value: &str| {
+            let body = format!(r#"{{"tickers":[{{"symbol":"PF_XBTUSD","last":{value}}}]}}"#);
+            parse_tickers(&body).unwrap()[0].last
+        };
+
+        assert_eq!(last("104218.5"), Some(104218.5));
+        assert_eq!(last(r#""104218.5""#), Some(104218.5));
+        assert_eq!(last(r#"" 42 ""#), Some(42.0));
+        assert_eq!(last("null"), None);
+        assert_eq!(last(r#""""#), None);
+        assert_eq!(last(r#""n/a""#), None);
+        assert_eq!(last("true"), None);
+        assert_eq!(last(r#"{"price":1}"#), None);
+    }
+
+    #[test]
+    fn endpoint_validation() {
+        let production = format!("{PRODUCTION_ENDPOINT}{TICKERS_PATH}");
+
+        assert_eq!(tickers_url(PRODUCTION_ENDPOINT), production);
+        assert_eq!(
+            tickers_url("https://demo-futures.kraken.com/"),
+            "https://demo-futures.kraken.com/derivatives/api/v3/tickers"
+        );
+        assert_eq!(
+            tickers_url("http://localhost:8080"),
+            "http://localhost:8080/derivatives/api/v3/tickers"
+        );
+        assert_eq!(tickers_url("demo-futures.kraken.com"), production);
+        assert_eq!(tickers_url("ftp://futures.kraken.com"), production);
+        assert_eq!(tickers_url(""), production);
+    }
+
+    #[test]
+    fn missing_tickers_are_an_error() {
+        for body in [
+            r#"{"result":"success"}"#,
+            r#"{"result":"success","tickers":null}"#,
+        ] {
+            assert!(matches!(
+                parse_tickers(body),
+                Err(FetchError::MissingTickers)
+            ));
+        }
+    }
+
+    #[test]
+    fn error_envelopes_fail_with_their_message() {
+        let failed = |body| match parse_tickers(body) {
+            Err(FetchError::Api(message)) => message,
+            other => panic!("expected an API error, got {other:?}"),
+        };
+
+        assert_eq!(
+            failed(
+                r#"{"result":"error","error":["Unavailable","marketSuspended"],"serverTime":"2025-05-12T09:30:00.000Z"}"#
+            ),
+            "Unavailable, marketSuspended"
+        );
+        assert_eq!(
+            failed(r#"{"result":"error","error":"Unavailable"}"#),
+            "Unavailable"
+        );
+        assert_eq!(failed(r#"{"result":"error"}"#), "unknown error");
+        assert!(matches!(
+            parse_tickers(r#"{"result":"error","error":"apiLimitExceeded"}"#),
+            Err(FetchError::RateLimited { .. })
+        ));
+    }
+
+    #[test]
+    fn empty_tickers_are_an_error() {
+        assert!(matches!(
+            parse_tickers(r#"{"result":"success","tickers":[]}"#),
+            Err(FetchError::NoMarkets)
+        ));
+    }
+}
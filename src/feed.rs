@@ -0,0 +1,211 @@
+//! Kraken Futures' WebSocket ticker feed: where it lives, what to send it and how to read what
+//! it sends back.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::data::{PRODUCTION_ENDPOINT, WatchItem, lenient_number};
+
+pub const FEED_PATH: &str = "/ws/v1";
+
+/// The feed URL of the API at `endpoint`, e.g. `wss://futures.kraken.com/ws/v1`, falling back
+/// to production if `endpoint` isn't an http(s) URL.
+pub fn feed_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    match reqwest::Url::parse(endpoint) {
+        Ok(url) if url.scheme() == "https" && url.has_host() => {
+            format!("wss{}{FEED_PATH}", &endpoint["https".len()..])
+        }
+        Ok(url) if url.scheme() == "http" && url.has_host() => {
+            format!("ws{}{FEED_PATH}", &endpoint["http".len()..])
+        }
+        _ => format!("wss{}{FEED_PATH}", &PRODUCTION_ENDPOINT["https".len()..]),
+    }
+}
+
+/// Asks for ticker updates of `product_ids`.
+pub fn subscribe(product_ids: &[String]) -> String {
+    json!({ "event": "subscribe", "feed": "ticker", "product_ids": product_ids }).to_string()
+}
+
+/// Stops the ticker updates of `product_ids`.
+pub fn unsubscribe(product_ids: &[String]) -> String {
+    json!({ "event": "unsubscribe", "feed": "ticker", "product_ids": product_ids }).to_string()
+}
+
+/// What the feed sent, as far as the dashboard cares.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedMessage {
+    /// A market's latest ticker, with only the fields the feed carries set.
+    Ticker(Box<WatchItem>),
+    /// Kraken rejected a request, e.g. an unknown product id.
+    Error(String),
+    /// Subscription confirmations, version info and other feeds.
+    Other,
+}
+
+/// A ticker as the feed sends it, which names several fields differently from REST.
+#[derive(Deserialize, Debug)]
+struct FeedTicker {
+    product_id: String,
+    #[serde(default, deserialize_with = "lenient_number")]
+    bid: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    ask: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    bid_size: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    ask_size: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    volume: Option<f64>,
+    #[serde(default, rename = "volumeQuote", deserialize_with = "lenient_number")]
+    volume_quote: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    last: Option<f64>,
+    /// 24h change in percent.
+    #[serde(default, deserialize_with = "lenient_number")]
+    change: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    open: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    high: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    low: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    index: Option<f64>,
+    #[serde(default, rename = "markPrice", deserialize_with = "lenient_number")]
+    mark_price: Option<f64>,
+    #[serde(default, rename = "openInterest", deserialize_with = "lenient_number")]
+    open_interest: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    funding_rate: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    funding_rate_prediction: Option<f64>,
+    suspended: Option<bool>,
+    post_only: Option<bool>,
+    tag: Option<String>,
+    pair: Option<String>,
+}
+
+impl From<FeedTicker> for WatchItem {
+    fn from(ticker: FeedTicker) -> Self {
+        WatchItem {
+            symbol: Some(ticker.product_id),
+            last: ticker.last,
+            tag: ticker.tag,
+            pair: ticker.pair,
+            mark_price: ticker.mark_price,
+            bid: ticker.bid,
+            bid_size: ticker.bid_size,
+            ask: ticker.ask,
+            ask_size: ticker.ask_size,
+            vol24h: ticker.volume,
+            volume_quote: ticker.volume_quote,
+            open_interest: ticker.open_interest,
+            open24h: ticker.open,
+            high24h: ticker.high,
+            low24h: ticker.low,
+            funding_rate: ticker.funding_rate,
+            funding_rate_prediction: ticker.funding_rate_prediction,
+            suspended: ticker.suspended,
+            index_price: ticker.index,
+            post_only: ticker.post_only,
+            change24h: ticker.change,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Envelope {
+    event: Option<String>,
+    feed: Option<String>,
+    message: Option<String>,
+}
+
+/// Reads one text message from the feed.
+pub fn parse(text: &str) -> Result<FeedMessage, serde_json::Error> {
+    let envelope: Envelope = serde_json::from_str(text)?;
+
+    let message = match (envelope.event.as_deref(), envelope.feed.as_deref()) {
+        (Some("error"), _) => FeedMessage::Error(
+            envelope
+                .message
+                .unwrap_or_else(|| "unknown error".to_string()),
+        ),
+        (None, Some("ticker")) => {
+            FeedMessage::Ticker(Box::new(serde_json::from_str::<FeedTicker>(text)?.into()))
+        }
+        _ => FeedMessage::Other,
+    };
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ticker message as captured from the production feed.
+    const TICKER: &str = r#"{"time":1746374832337,"product_id":"PF_XBTUSD","funding_rate":-0.117370237526,"funding_rate_prediction":-0.125971447323,"relative_funding_rate":-1.2267e-6,"relative_funding_rate_prediction":-1.3166e-6,"next_funding_rate_time":1746378000000,"feed":"ticker","bid":95684.0,"ask":95685.0,"bid_size":0.2342,"ask_size":0.0426,"volume":2427.1172,"dtm":0,"leverage":"50x","index":95690.18,"premium":0.0,"last":95684.0,"change":-0.71,"suspended":false,"tag":"perpetual","pair":"XBT:USD","openInterest":2132.6703,"markPrice":95686.743,"maturityTime":0,"post_only":false,"volumeQuote":231711468.5853}"#;
+
+    #[test]
+    fn ticker_messages_map_onto_rest_fields() {
+        let FeedMessage::Ticker(item) = parse(TICKER).unwrap() else {
+            panic!("not a ticker");
+        };
+
+        assert_eq!(item.symbol.as_deref(), Some("PF_XBTUSD"));
+        assert_eq!(item.mark_price, Some(95686.743));
+        assert_eq!(item.index_price, Some(95690.18));
+        assert_eq!(item.vol24h, Some(2427.1172));
+        assert_eq!(item.volume_quote, Some(231711468.5853));
+        assert_eq!(item.open_interest, Some(2132.6703));
+        assert_eq!(item.change24h, Some(-0.71));
+        assert_eq!(item.base_quote(), Some(("XBT", "USD")));
+        // The feed doesn't carry these, so merging keeps the snapshot's.
+        assert_eq!(item.last_time, None);
+        assert_eq!(item.open24h, None);
+    }
+
+    #[test]
+    fn control_messages() {
+        assert_eq!(
+            parse(r#"{"event":"error","message":"Invalid product id"}"#).unwrap(),
+            FeedMessage::Error("Invalid product id".to_string())
+        );
+        assert_eq!(
+            parse(r#"{"event":"subscribed","feed":"ticker","product_ids":["PF_XBTUSD"]}"#).unwrap(),
+            FeedMessage::Other
+        );
+        assert_eq!(
+            parse(r#"{"event":"info","version":1}"#).unwrap(),
+            FeedMessage::Other
+        );
+        assert!(parse("not json").is_err());
+        assert_eq!(
+            subscribe(&["PF_XBTUSD".to_string()]),
+            r#"{"event":"subscribe","feed":"ticker","product_ids":["PF_XBTUSD"]}"#
+        );
+    }
+
+    #[test]
+    fn feed_urls_follow_the_endpoint() {
+        assert_eq!(
+            feed_url(PRODUCTION_ENDPOINT),
+            "wss://futures.kraken.com/ws/v1"
+        );
+        assert_eq!(
+            feed_url("https://demo-futures.kraken.com/"),
+            "wss://demo-futures.kraken.com/ws/v1"
+        );
+        assert_eq!(
+            feed_url("http://localhost:8080"),
+            "ws://localhost:8080/ws/v1"
+        );
+        assert_eq!(
+            feed_url("ftp://example.com"),
+            "wss://futures.kraken.com/ws/v1"
+        );
+    }
+}
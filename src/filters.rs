@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use crate::{WatchItem, allowlist};
+
+/// Every user-controlled filter narrowing down the rows shown in the table.
+///
+/// `Default` is the unfiltered state, which is what "Clear filters" restores.
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    /// Only show markets whose book is locked or crossed.
+    pub crossed_only: bool,
+    /// Leave out markets Kraken reports as suspended.
+    pub hide_suspended: bool,
+    /// Leave out markets that traded less than this over 24h, or didn't report it; 0 is off.
+    pub min_volume: f64,
+    /// Only show markets listed in the allowlist file, when that filter is on and loaded.
+    pub allowlist: Option<HashSet<String>>,
+    /// Only show markets whose symbol or pair contains this, ignoring case.
+    pub search_query: String,
+}
+
+impl Filters {
+    /// How many filters currently hide rows.
+    pub fn active_count(&self) -> usize {
+        [
+            self.crossed_only,
+            self.hide_suspended,
+            self.min_volume > 0.0,
+            self.allowlist.is_some(),
+            !self.search_query.trim().is_empty(),
+        ]
+        .into_iter()
+        .filter(|active| *active)
+        .count()
+    }
+
+    pub fn matches(&self, item: &WatchItem) -> bool {
+        (!self.crossed_only || item.is_crossed())
+            && !(self.hide_suspended && item.suspended == Some(true))
+            && (self.min_volume <= 0.0 || item.vol24h.is_some_and(|vol| vol >= self.min_volume))
+            && self
+                .allowlist
+                .as_ref()
+                .is_none_or(|allowlist| allowlist::contains(allowlist, item))
+            && self.matches_search(item)
+    }
+
+    fn matches_search(&self, item: &WatchItem) -> bool {
+        let query = self.search_query.trim().to_lowercase();
+        query.is_empty()
+            || [&item.symbol, &item.pair]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(&query))
+    }
+}
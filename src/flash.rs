@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{ColumnKind, WatchItem};
+
+/// A cell tint left by a change, fading out over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flash {
+    pub rose: bool,
+    pub started: Instant,
+}
+
+impl Flash {
+    /// How strong the tint still is at `now`, from 1 when it starts down to 0 after `duration`.
+    pub fn strength(&self, now: Instant, duration: Duration) -> f32 {
+        let elapsed = now.saturating_duration_since(self.started);
+        1.0 - (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+    }
+}
+
+/// Diffs the `kind` field between two fetches, keyed by symbol: `true` if the value rose.
+///
+/// Markets missing from either fetch, or without a value in either, don't flash.
+pub fn changes(kind: ColumnKind, old: &[WatchItem], new: &[WatchItem]) -> HashMap<String, bool> {
+    let previous: HashMap<&str, f64> = old
+        .iter()
+        .filter_map(|item| Some((item.symbol.as_deref()?, kind.number(item)?)))
+        .collect();
+
+    new.iter()
+        .filter_map(|item| {
+            let symbol = item.symbol.as_deref()?;
+            let (before, after) = (*previous.get(symbol)?, kind.number(item)?);
+            (before != after).then(|| (symbol.to_string(), after > before))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(symbol: &str, mark_price: f64) -> WatchItem {
+        WatchItem {
+            symbol: Some(symbol.to_string()),
+            mark_price: Some(mark_price),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn new_symbols_do_not_flash() {
+        let old = [item("PF_A", 1.0)];
+        let new = [item("PF_A", 2.0), item("PF_B", 5.0)];
+
+        let flashes = changes(ColumnKind::MarkPrice, &old, &new);
+        assert_eq!(flashes, HashMap::from([("PF_A".to_string(), true)]));
+    }
+
+    #[test]
+    fn strength_fades_to_zero() {
+        let started = Instant::now();
+        let flash = Flash {
+            rose: true,
+            started,
+        };
+        let duration = Duration::from_millis(500);
+
+        assert_eq!(flash.strength(started, duration), 1.0);
+        assert_eq!(flash.strength(started + duration / 2, duration), 0.5);
+        assert_eq!(flash.strength(started + duration * 2, duration), 0.0);
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Formats `value` with `decimals` fraction digits, separating groups of thousands and the
+/// fraction with the given marks, e.g. `(',', '.')` for `12,345.67`.
+pub fn group_thousands(value: f64, decimals: usize, (thousands, decimal): (char, char)) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(formatted.len() + integer.len() / 3 + 1);
+    // Rounding can turn a tiny negative number into zero, which should not keep its sign.
+    if value < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        grouped.push('-');
+    }
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push(decimal);
+        grouped.push_str(fraction);
+    }
+
+    grouped
+}
+
+/// What goes between groups of thousands.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Separator {
+    #[default]
+    Comma,
+    Space,
+    /// Whatever the system locale uses, from `LC_ALL`, `LC_NUMERIC` or `LANG`.
+    Locale,
+}
+
+impl Separator {
+    pub const ALL: [Separator; 3] = [Separator::Comma, Separator::Space, Separator::Locale];
+
+    /// The thousands separator and decimal mark.
+    pub fn marks(&self) -> (char, char) {
+        match self {
+            Separator::Comma => (',', '.'),
+            Separator::Space => (NO_BREAK_SPACE, '.'),
+            Separator::Locale => {
+                let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+                    .into_iter()
+                    .filter_map(|var| std::env::var(var).ok())
+                    .find(|value| !value.is_empty())
+                    .unwrap_or_default();
+                locale_marks(&locale)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Separator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Separator::Comma => "1,234",
+            Separator::Space => "1 234",
+            Separator::Locale => "Locale",
+        })
+    }
+}
+
+/// Keeps a grouped number on one line.
+const NO_BREAK_SPACE: char = '\u{a0}';
+
+/// The marks of a POSIX locale name like `de_DE.UTF-8`, by language. English and anything
+/// unrecognized get commas.
+fn locale_marks(locale: &str) -> (char, char) {
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+    match language {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" => ('.', ','),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" => {
+            (NO_BREAK_SPACE, ',')
+        }
+        _ => (',', '.'),
+    }
+}
+
+/// How a quantity column writes its numbers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberStyle {
+    #[default]
+    Plain,
+    /// Thousands separators, e.g. `12,345,678`.
+    Grouped,
+    /// A K/M/B/T suffix, e.g. `12.3M`.
+    Abbreviated,
+}
+
+impl NumberStyle {
+    pub const ALL: [NumberStyle; 3] = [
+        NumberStyle::Plain,
+        NumberStyle::Grouped,
+        NumberStyle::Abbreviated,
+    ];
+}
+
+impl fmt::Display for NumberStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NumberStyle::Plain => "Plain",
+            NumberStyle::Grouped => "Grouped",
+            NumberStyle::Abbreviated => "K/M/B",
+        })
+    }
+}
+
+/// How the 24h change cell is written.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeDisplay {
+    #[default]
+    Percent,
+    Absolute,
+    Both,
+}
+
+impl ChangeDisplay {
+    pub const ALL: [ChangeDisplay; 3] = [
+        ChangeDisplay::Percent,
+        ChangeDisplay::Absolute,
+        ChangeDisplay::Both,
+    ];
+
+    /// Writes a change as e.g. `+3.42%`, `+1234.5` or `+1234.5 (+3.42%)`, falling back to
+    /// whichever part is known when the other is missing.
+    pub fn format(&self, percent: Option<f64>, absolute: Option<f64>) -> Option<String> {
+        let percent = percent.map(|percent| format!("{percent:+.2}%"));
+        let absolute = absolute.map(signed);
+
+        match (self, absolute, percent) {
+            (ChangeDisplay::Both, Some(absolute), Some(percent)) => {
+                Some(format!("{absolute} ({percent})"))
+            }
+            (ChangeDisplay::Absolute, Some(absolute), _) => Some(absolute),
+            (ChangeDisplay::Percent, _, Some(percent)) => Some(percent),
+            (_, absolute, percent) => percent.or(absolute),
+        }
+    }
+}
+
+impl fmt::Display for ChangeDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeDisplay::Percent => "Percent",
+            ChangeDisplay::Absolute => "Absolute",
+            ChangeDisplay::Both => "Both",
+        })
+    }
+}
+
+/// `value` with an explicit sign, rounded to hide floating point noise like `0.30000000000000004`.
+fn signed(value: f64) -> String {
+    let rounded = format!("{value:+.8}");
+    rounded
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// How many decimals a price column shows.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// Enough decimals for the value's magnitude, so both 64,000 and 0.000123 read well.
+    #[default]
+    Auto,
+    Fixed(usize),
+}
+
+impl Precision {
+    pub const ALL: [Precision; 10] = [
+        Precision::Auto,
+        Precision::Fixed(0),
+        Precision::Fixed(1),
+        Precision::Fixed(2),
+        Precision::Fixed(3),
+        Precision::Fixed(4),
+        Precision::Fixed(5),
+        Precision::Fixed(6),
+        Precision::Fixed(7),
+        Precision::Fixed(8),
+    ];
+
+    pub fn format(&self, value: f64) -> String {
+        let decimals = match self {
+            Precision::Auto => auto_decimals(value),
+            Precision::Fixed(decimals) => *decimals,
+        };
+
+        format!("{value:.decimals$}")
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Precision::Auto => f.write_str("Auto"),
+            Precision::Fixed(decimals) => write!(f, "{decimals} decimals"),
+        }
+    }
+}
+
+/// Decimals keeping about five significant digits for prices below a thousand.
+fn auto_decimals(value: f64) -> usize {
+    match value.abs() {
+        1000.0.. => 2,
+        1.0.. => 4,
+        0.01.. => 6,
+        _ => 8,
+    }
+}
+
+/// Suffixes for successive powers of a thousand.
+const UNITS: [&str; 5] = ["", "K", "M", "B", "T"];
+
+/// Shortens `value` to a number below a thousand with a unit suffix, e.g. `1.2M` for 1,234,567.
+pub fn abbreviate(value: f64, decimals: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let mut unit = 0;
+    let mut scaled = value.abs();
+    while scaled >= 1000.0 && unit < UNITS.len() - 1 {
+        scaled /= 1000.0;
+        unit += 1;
+    }
+    // Rounding may carry into the next unit, e.g. 999,950 at one decimal.
+    if format!("{:.*}", decimals, scaled).parse::<f64>() == Ok(1000.0) && unit < UNITS.len() - 1 {
+        scaled /= 1000.0;
+        unit += 1;
+    }
+
+    let formatted = format!("{:.*}{}", decimals, scaled, UNITS[unit]);
+    if value < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        format!("-{formatted}")
+    } else {
+        formatted
+    }
+}
+
+/// Writes a fraction as a percentage without trailing zeros, e.g. `0.00125%` for 0.0000125.
+pub fn percent(fraction: f64) -> String {
+    let formatted = format!("{:.5}", fraction * 100.0);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" => "0%".to_string(),
+        _ => format!("{trimmed}%"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abbreviate_boundaries() {
+        assert_eq!(abbreviate(999.0, 0), "999");
+        assert_eq!(abbreviate(999.0, 1), "999.0");
+        assert_eq!(abbreviate(1000.0, 0), "1K");
+        assert_eq!(abbreviate(1000.0, 1), "1.0K");
+        assert_eq!(abbreviate(1000.0, 2), "1.00K");
+        assert_eq!(abbreviate(1_000_000.0, 0), "1M");
+        assert_eq!(abbreviate(1_000_000.0, 1), "1.0M");
+        assert_eq!(abbreviate(1_000_000.0, 2), "1.00M");
+    }
+
+    #[test]
+    fn abbreviate_rounding() {
+        assert_eq!(abbreviate(1_234_567.0, 1), "1.2M");
+        assert_eq!(abbreviate(1_234_567.0, 2), "1.23M");
+        assert_eq!(abbreviate(999_950.0, 1), "1.0M");
+        assert_eq!(abbreviate(999_949.0, 1), "999.9K");
+        assert_eq!(abbreviate(999.6, 0), "1K");
+        assert_eq!(abbreviate(-1500.0, 1), "-1.5K");
+        assert_eq!(abbreviate(-0.01, 1), "0.0");
+        assert_eq!(abbreviate(2.5e15, 0), "2500T");
+    }
+
+    const COMMA: (char, char) = (',', '.');
+
+    #[test]
+    fn group_thousands_signs_and_decimals() {
+        assert_eq!(group_thousands(12_345_678.0, 0, COMMA), "12,345,678");
+        assert_eq!(group_thousands(-12_345_678.0, 0, COMMA), "-12,345,678");
+        assert_eq!(group_thousands(0.0, 0, COMMA), "0");
+        assert_eq!(group_thousands(0.0, 2, COMMA), "0.00");
+        assert_eq!(group_thousands(999.0, 0, COMMA), "999");
+        assert_eq!(group_thousands(1000.0, 0, COMMA), "1,000");
+        assert_eq!(group_thousands(1234.5678, 2, COMMA), "1,234.57");
+        assert_eq!(group_thousands(-1_234_567.891, 1, COMMA), "-1,234,567.9");
+        assert_eq!(group_thousands(-0.001, 2, COMMA), "0.00");
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(
+            group_thousands(1_234_567.5, 1, Separator::Space.marks()),
+            "1\u{a0}234\u{a0}567.5"
+        );
+        assert_eq!(group_thousands(1_234_567.5, 1, ('.', ',')), "1.234.567,5");
+        assert_eq!(locale_marks("de_DE.UTF-8"), ('.', ','));
+        assert_eq!(locale_marks("fr_FR"), (NO_BREAK_SPACE, ','));
+        assert_eq!(locale_marks("en_US.UTF-8"), (',', '.'));
+        assert_eq!(locale_marks("C"), (',', '.'));
+        assert_eq!(locale_marks(""), (',', '.'));
+    }
+
+    #[test]
+    fn percent_from_fraction() {
+        assert_eq!(percent(0.0000125), "0.00125%");
+        assert_eq!(percent(-0.0001), "-0.01%");
+        assert_eq!(percent(0.0000125 * 8760.0), "10.95%");
+        assert_eq!(percent(0.0), "0%");
+        assert_eq!(percent(-0.0000000001), "0%");
+    }
+
+    #[test]
+    fn precision_by_magnitude() {
+        assert_eq!(Precision::Auto.format(64_123.456), "64123.46");
+        assert_eq!(Precision::Auto.format(1.5), "1.5000");
+        assert_eq!(Precision::Auto.format(0.000_123_000_000_01), "0.00012300");
+        assert_eq!(Precision::Fixed(1).format(0.000_123), "0.0");
+    }
+
+    #[test]
+    fn change_display_fallbacks() {
+        let both = ChangeDisplay::Both;
+        assert_eq!(
+            both.format(Some(3.42), Some(1234.5)).as_deref(),
+            Some("+1234.5 (+3.42%)")
+        );
+        assert_eq!(both.format(None, Some(-0.3)).as_deref(), Some("-0.3"));
+        assert_eq!(
+            ChangeDisplay::Absolute.format(Some(3.42), None).as_deref(),
+            Some("+3.42%")
+        );
+        assert_eq!(
+            ChangeDisplay::Percent.format(None, Some(2.0)).as_deref(),
+            Some("+2")
+        );
+        assert_eq!(ChangeDisplay::Percent.format(None, None), None);
+    }
+}
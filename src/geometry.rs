@@ -0,0 +1,96 @@
+use std::sync::OnceLock;
+
+use iced::{Point, Size, window};
+use serde::{Deserialize, Serialize};
+
+/// Restored windows are at least this big, so a bad save can't leave one too small to use.
+const MIN_SIZE: Size = Size::new(320.0, 240.0);
+
+/// The saved position, read when the window opens since `Position::SpecificWith` takes a plain
+/// function.
+static RESTORED_POSITION: OnceLock<Point> = OnceLock::new();
+
+/// The window's size and position as last left, in logical pixels.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    /// Unset until the window is moved, and on platforms that don't say where windows are.
+    pub position: Option<(f32, f32)>,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        let size = window::Settings::default().size;
+
+        Self {
+            width: size.width,
+            height: size.height,
+            position: None,
+        }
+    }
+}
+
+impl WindowGeometry {
+    /// Settings opening the window at this size and position.
+    pub fn settings(self) -> window::Settings {
+        let position = match self.position {
+            Some((x, y)) if RESTORED_POSITION.set(Point::new(x, y)).is_ok() => {
+                window::Position::SpecificWith(restored_position)
+            }
+            _ => window::Position::Default,
+        };
+
+        window::Settings {
+            size: Size::new(
+                self.width.max(MIN_SIZE.width),
+                self.height.max(MIN_SIZE.height),
+            ),
+            position,
+            ..window::Settings::default()
+        }
+    }
+}
+
+fn restored_position(window: Size, display: Size) -> Point {
+    clamp_onto_display(
+        RESTORED_POSITION.get().copied().unwrap_or(Point::ORIGIN),
+        window,
+        display,
+    )
+}
+
+/// Moves `position` so a `window` there fits on `display`, or starts at its top left corner if
+/// it's bigger.
+///
+/// iced only reports the primary display, so a window left on another one, or on one since
+/// disconnected, reopens at the nearest edge of the primary display.
+pub fn clamp_onto_display(position: Point, window: Size, display: Size) -> Point {
+    let clamp = |value: f32, room: f32| value.min(room.max(0.0)).max(0.0);
+
+    Point::new(
+        clamp(position.x, display.width - window.width),
+        clamp(position.y, display.height - window.height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_clamped_onto_the_display() {
+        let display = Size::new(1920.0, 1080.0);
+        let window = Size::new(800.0, 600.0);
+        let clamp = |x, y| clamp_onto_display(Point::new(x, y), window, display);
+
+        assert_eq!(clamp(100.0, 50.0), Point::new(100.0, 50.0));
+        assert_eq!(clamp(2500.0, 400.0), Point::new(1120.0, 400.0));
+        assert_eq!(clamp(-300.0, -20.0), Point::ORIGIN);
+        assert_eq!(clamp(100.0, 5000.0), Point::new(100.0, 480.0));
+        assert_eq!(
+            clamp_onto_display(Point::new(50.0, 50.0), Size::new(2500.0, 1500.0), display),
+            Point::ORIGIN
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::WatchItem;
+
+/// Group name for markets whose pair can't be parsed, or that have no tag.
+const OTHER: &str = "Other";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    #[default]
+    None,
+    Quote,
+    Base,
+    /// The contract type, like `perpetual` or `month`.
+    Tag,
+}
+
+impl Grouping {
+    pub const ALL: [Grouping; 4] = [
+        Grouping::None,
+        Grouping::Quote,
+        Grouping::Base,
+        Grouping::Tag,
+    ];
+
+    fn key<'a>(&self, item: &'a WatchItem) -> &'a str {
+        let parts = item.base_quote();
+        match self {
+            Grouping::None => "",
+            Grouping::Quote => parts.map_or(OTHER, |(_, quote)| quote),
+            Grouping::Base => parts.map_or(OTHER, |(base, _)| base),
+            Grouping::Tag => item
+                .tag
+                .as_deref()
+                .filter(|tag| !tag.is_empty())
+                .unwrap_or(OTHER),
+        }
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Grouping::None => "No grouping",
+            Grouping::Quote => "Group by quote",
+            Grouping::Base => "Group by base",
+            Grouping::Tag => "Group by tag",
+        })
+    }
+}
+
+/// A group header with aggregates over its markets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub key: String,
+    pub count: usize,
+    pub collapsed: bool,
+    pub avg_change24h: Option<f64>,
+    pub vol24h: f64,
+    pub volume_quote: f64,
+}
+
+/// A line of the table: either a market or the header of a group of markets.
+#[derive(Debug, Clone)]
+pub enum TableRow {
+    Group(Group),
+    Market(Box<WatchItem>),
+}
+
+/// Lays out `rows` under group headers, keeping their relative order within each group.
+///
+/// Groups are sorted by name with "Other" last, and collapsed groups only show their header.
+pub fn group(rows: &[WatchItem], grouping: Grouping, collapsed: &HashSet<String>) -> Vec<TableRow> {
+    if grouping == Grouping::None {
+        return rows
+            .iter()
+            .map(|item| TableRow::Market(Box::new(item.clone())))
+            .collect();
+    }
+
+    let mut groups: BTreeMap<(bool, &str), Vec<&WatchItem>> = BTreeMap::new();
+    for item in rows {
+        let key = grouping.key(item);
+        groups.entry((key == OTHER, key)).or_default().push(item);
+    }
+
+    let mut table = Vec::with_capacity(rows.len() + groups.len());
+    for ((_, key), items) in groups {
+        let changes: Vec<f64> = items.iter().filter_map(|item| item.change24h).collect();
+        let is_collapsed = collapsed.contains(key);
+
+        table.push(TableRow::Group(Group {
+            key: key.to_string(),
+            count: items.len(),
+            collapsed: is_collapsed,
+            avg_change24h: (!changes.is_empty())
+                .then(|| changes.iter().sum::<f64>() / changes.len() as f64),
+            vol24h: items.iter().filter_map(|item| item.vol24h).sum(),
+            volume_quote: items.iter().filter_map(|item| item.volume_quote).sum(),
+        }));
+        if !is_collapsed {
+            table.extend(
+                items
+                    .into_iter()
+                    .map(|item| TableRow::Market(Box::new(item.clone()))),
+            );
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(symbol: &str, tag: Option<&str>) -> WatchItem {
+        WatchItem {
+            symbol: Some(symbol.to_string()),
+            tag: tag.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tag_groups_keep_row_order() {
+        let rows = [
+            tagged("PF_B", Some("perpetual")),
+            tagged("FI_A", Some("month")),
+            tagged("PF_A", Some("perpetual")),
+            tagged("X", None),
+        ];
+        let collapsed = HashSet::from(["month".to_string()]);
+
+        let lines: Vec<String> = group(&rows, Grouping::Tag, &collapsed)
+            .into_iter()
+            .map(|row| match row {
+                TableRow::Group(group) => format!("{} ({})", group.key, group.count),
+                TableRow::Market(item) => item.symbol.unwrap_or_default(),
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "month (1)",
+                "perpetual (2)",
+                "PF_B",
+                "PF_A",
+                "Other (1)",
+                "X"
+            ]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use iced::alignment;
+use iced::mouse;
+use iced::widget::canvas::{Cache, Geometry, Program, Text};
+use iced::{Point, Rectangle, Renderer, Size, Theme};
+
+use crate::{GREEN, RED};
+
+/// Width of a bucket, in percentage points of 24h change.
+const BUCKET_WIDTH: f64 = 2.0;
+/// Changes beyond ±`RANGE` percent are counted in the outermost buckets.
+const RANGE: f64 = 20.0;
+const LABEL_HEIGHT: f32 = 14.0;
+
+/// The distribution of 24h changes across a set of markets.
+#[derive(Default)]
+pub struct Histogram {
+    counts: Vec<usize>,
+    cache: Cache,
+}
+
+impl Histogram {
+    pub fn new(changes: impl IntoIterator<Item = f64>) -> Self {
+        let buckets = (2.0 * RANGE / BUCKET_WIDTH) as usize;
+        let mut counts = vec![0; buckets];
+
+        for change in changes.into_iter().filter(|change| !change.is_nan()) {
+            let index = ((change + RANGE) / BUCKET_WIDTH).floor();
+            counts[index.clamp(0.0, (buckets - 1) as f64) as usize] += 1;
+        }
+
+        Self {
+            counts,
+            cache: Cache::new(),
+        }
+    }
+}
+
+impl<Message> Program<Message> for Histogram {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            let Some(max) = self.counts.iter().copied().max().filter(|max| *max > 0) else {
+                return;
+            };
+            let bar_width = frame.width() / self.counts.len() as f32;
+            let chart_height = frame.height() - LABEL_HEIGHT;
+
+            for (index, count) in self.counts.iter().enumerate() {
+                let lower = index as f64 * BUCKET_WIDTH - RANGE;
+                let height = chart_height * *count as f32 / max as f32;
+
+                frame.fill_rectangle(
+                    Point::new(index as f32 * bar_width + 1.0, chart_height - height),
+                    Size::new((bar_width - 2.0).max(1.0), height),
+                    if lower < 0.0 { RED } else { GREEN },
+                );
+            }
+
+            for (label, x, alignment) in [
+                (format!("≤-{RANGE}%"), 0.0, alignment::Horizontal::Left),
+                (
+                    "0%".to_string(),
+                    frame.width() / 2.0,
+                    alignment::Horizontal::Center,
+                ),
+                (
+                    format!("≥+{RANGE}%"),
+                    frame.width(),
+                    alignment::Horizontal::Right,
+                ),
+            ] {
+                frame.fill_text(Text {
+                    content: label,
+                    position: Point::new(x, chart_height),
+                    color: theme.palette().text,
+                    size: 12.into(),
+                    horizontal_alignment: alignment,
+                    ..Text::default()
+                });
+            }
+        });
+
+        vec![geometry]
+    }
+}
@@ -0,0 +1,6 @@
+//! Fetching and parsing of Kraken tickers, usable without the GUI.
+
+pub mod data;
+pub mod feed;
+pub mod source;
+pub mod websocket;
@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+use std::io;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::time::timeout;
+
+use crate::Message;
+use krader::feed::{self, FeedMessage};
+use krader::websocket::{self, Incoming, Reader, WebSocketError, Writer};
+
+/// How long to wait before the first reconnect after the feed drops. Each failure in a row
+/// doubles it, up to [`MAX_RECONNECT_DELAY`].
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+/// How many times in a row connecting may fail before the feed is given up on.
+const MAX_RECONNECT_ATTEMPTS: u32 = 8;
+
+/// How often to ping the server. Kraken drops connections that stay quiet for a minute.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the feed may go without sending anything, pongs included, before the connection is
+/// taken for dead and replaced.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The state of the live feed connection.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The feed is up. Send it the symbols to follow; it keeps the subscriptions in step.
+    Connected {
+        markets: mpsc::UnboundedSender<Vec<String>>,
+        /// How many times the feed has reconnected since it was first up.
+        reconnects: u32,
+    },
+    /// The connection dropped, and will be retried shortly.
+    Disconnected,
+    /// Connecting failed [`MAX_RECONNECT_ATTEMPTS`] times in a row, and won't be retried.
+    GaveUp,
+}
+
+/// Streams ticker updates from the feed at `url` as [`Message::TickerUpdate`]s, reconnecting
+/// whenever the connection drops, until the subscription is dropped.
+pub fn connect(url: String) -> impl Stream<Item = Message> {
+    iced::stream::channel(100, move |mut output| async move {
+        let mut connections = 0;
+        let mut failures = 0;
+        loop {
+            let result = match timeout(IDLE_TIMEOUT, websocket::connect(&url)).await {
+                Ok(Ok((reader, writer))) => {
+                    log::info!("Live feed {url} connected");
+                    let (markets, wanted) = mpsc::unbounded();
+                    let connected = Event::Connected {
+                        markets,
+                        reconnects: connections,
+                    };
+                    if output.send(Message::Feed(connected)).await.is_err() {
+                        return;
+                    }
+                    connections += 1;
+                    failures = 0;
+                    follow(reader, writer, wanted, &mut output).await
+                }
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err(timed_out("connecting")),
+            };
+            let reason = match result {
+                Ok(()) => "closed by the server".to_string(),
+                Err(e) => e.to_string(),
+            };
+
+            failures += 1;
+            if failures > MAX_RECONNECT_ATTEMPTS {
+                log::error!(
+                    "Live feed {url} failed {failures} times in a row, giving up: {reason}"
+                );
+                let _ = output.send(Message::Feed(Event::GaveUp)).await;
+                return;
+            }
+            if failures == 1 {
+                log::warn!("Live feed {url} dropped: {reason}");
+            } else {
+                log::error!("Live feed {url} failed {failures} times in a row: {reason}");
+            }
+            if output
+                .send(Message::Feed(Event::Disconnected))
+                .await
+                .is_err()
+            {
+                return;
+            }
+            tokio::time::sleep(reconnect_delay(failures)).await;
+        }
+    })
+}
+
+/// How long to wait before reconnecting after `failures` failures in a row.
+fn reconnect_delay(failures: u32) -> Duration {
+    RECONNECT_DELAY
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(MAX_RECONNECT_DELAY)
+}
+
+fn timed_out(what: &str) -> WebSocketError {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!(
+            "nothing received for {}s while {what}",
+            IDLE_TIMEOUT.as_secs()
+        ),
+    )
+    .into()
+}
+
+/// Runs one connection until it drops.
+async fn follow(
+    mut reader: Reader,
+    writer: Writer,
+    wanted: mpsc::UnboundedReceiver<Vec<String>>,
+    output: &mut mpsc::Sender<Message>,
+) -> Result<(), WebSocketError> {
+    let (pongs, pending_pongs) = mpsc::unbounded::<Vec<u8>>();
+
+    // Reads and writes run side by side, as a frame half read can't be set aside to write.
+    let reading = async {
+        loop {
+            let incoming = timeout(IDLE_TIMEOUT, reader.recv())
+                .await
+                .map_err(|_| timed_out("streaming"))??;
+            match incoming {
+                Incoming::Text(text) => match feed::parse(&text) {
+                    Ok(FeedMessage::Ticker(item)) => {
+                        if output.send(Message::TickerUpdate(item)).await.is_err() {
+                            return Ok::<(), WebSocketError>(());
+                        }
+                    }
+                    Ok(FeedMessage::Error(message)) => log::warn!("Live feed error: {message}"),
+                    Ok(FeedMessage::Other) => {}
+                    Err(e) => log::debug!("Ignoring an unreadable feed message: {e}"),
+                },
+                Incoming::Ping(payload) => {
+                    let _ = pongs.unbounded_send(payload);
+                }
+                Incoming::Close(_) => return Ok(()),
+                Incoming::Binary(_) | Incoming::Pong(_) => {}
+            }
+        }
+    };
+    let writing = write(writer, wanted, pending_pongs);
+
+    tokio::select! {
+        result = reading => result,
+        result = writing => result,
+    }
+}
+
+/// Keeps the subscriptions in step with the wanted markets, answers pings and sends heartbeats.
+async fn write(
+    mut writer: Writer,
+    mut wanted: mpsc::UnboundedReceiver<Vec<String>>,
+    mut pending_pongs: mpsc::UnboundedReceiver<Vec<u8>>,
+) -> Result<(), WebSocketError> {
+    let mut subscribed = BTreeSet::new();
+    let mut heartbeat = tokio::time::interval(PING_INTERVAL);
+    // The first tick is immediate, and the connection has only just opened.
+    heartbeat.tick().await;
+    loop {
+        tokio::select! {
+            Some(symbols) = wanted.next() => {
+                let symbols: BTreeSet<String> = symbols.into_iter().collect();
+                let dropped: Vec<String> = subscribed.difference(&symbols).cloned().collect();
+                let added: Vec<String> = symbols.difference(&subscribed).cloned().collect();
+                if !dropped.is_empty() {
+                    writer.send_text(&feed::unsubscribe(&dropped)).await?;
+                }
+                if !added.is_empty() {
+                    writer.send_text(&feed::subscribe(&added)).await?;
+                }
+                subscribed = symbols;
+            }
+            Some(payload) = pending_pongs.next() => writer.send_pong(&payload).await?,
+            _ = heartbeat.tick() => writer.send_ping(&[]).await?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnects_back_off_up_to_a_cap() {
+        assert_eq!(reconnect_delay(1), Duration::from_secs(5));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(10));
+        assert_eq!(reconnect_delay(4), Duration::from_secs(40));
+        assert_eq!(reconnect_delay(7), MAX_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), MAX_RECONNECT_DELAY);
+    }
+}
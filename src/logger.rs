@@ -0,0 +1,99 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Environment variable picking what gets logged, like `debug` or `warn,krader=debug`.
+const FILTER_ENV: &str = "RUST_LOG";
+
+/// The level of targets no directive names.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// Writes log records to stderr, filtered by `RUST_LOG`.
+struct Logger {
+    /// Target prefixes and their levels; a directive without a target covers every target.
+    directives: Vec<(Option<String>, LevelFilter)>,
+}
+
+impl Logger {
+    /// The level of the directive naming the longest prefix of `target`.
+    fn level(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.as_deref().is_none_or(|prefix| {
+                    target == prefix || target.starts_with(&format!("{prefix}::"))
+                })
+            })
+            .max_by_key(|(prefix, _)| prefix.as_deref().map_or(0, str::len))
+            .map_or(DEFAULT_LEVEL, |(_, level)| *level)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Reads comma-separated `level` and `target=level` directives, skipping ones that don't parse.
+fn directives(filter: &str) -> Vec<(Option<String>, LevelFilter)> {
+    filter
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .filter_map(|directive| match directive.split_once('=') {
+            Some((target, level)) => {
+                Some((Some(target.trim().to_string()), level.trim().parse().ok()?))
+            }
+            None => Some((None, directive.parse().ok()?)),
+        })
+        .collect()
+}
+
+/// Installs the stderr logger. Only the first call has any effect.
+pub fn init() {
+    let logger = Logger {
+        directives: directives(&std::env::var(FILTER_ENV).unwrap_or_default()),
+    };
+    let max = logger
+        .directives
+        .iter()
+        .map(|(_, level)| *level)
+        .chain([DEFAULT_LEVEL])
+        .max()
+        .unwrap_or(DEFAULT_LEVEL);
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_directive_wins() {
+        let logger = Logger {
+            directives: directives("info, krader=debug,krader::data=trace,wgpu=off,bogus=loud"),
+        };
+
+        assert_eq!(logger.level("krader"), LevelFilter::Debug);
+        assert_eq!(logger.level("krader::data"), LevelFilter::Trace);
+        assert_eq!(logger.level("krader_other"), LevelFilter::Info);
+        assert_eq!(logger.level("wgpu"), LevelFilter::Off);
+        assert_eq!(logger.level("iced"), LevelFilter::Info);
+        assert_eq!(logger.directives.len(), 4);
+
+        let empty = Logger {
+            directives: directives(""),
+        };
+        assert_eq!(empty.level("krader"), DEFAULT_LEVEL);
+    }
+}
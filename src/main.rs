@@ -1,209 +1,3221 @@
-use std::time::Duration;
+mod alerts;
+mod allowlist;
+mod args;
+mod clock;
+mod config;
+mod filters;
+mod flash;
+mod format;
+mod geometry;
+mod grouping;
+mod histogram;
+mod live;
+mod logger;
+mod mock;
+mod palette;
+mod report;
+mod row_action;
+mod rules;
+mod snapshot;
+mod sort;
+mod sparkline;
+mod summary;
+mod theme;
+mod transform;
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use iced::{
-    application, time::every, widget::{column, container, horizontal_space, responsive, scrollable, text}, Color, Element, Length, Renderer, Subscription, Task, Theme
+    Color, Element, Font, Length, Rectangle, Renderer, Subscription, Task, Theme, application,
+    time::every,
+    widget::{
+        button, canvas, checkbox, column, container, horizontal_space, mouse_area, pick_list,
+        responsive, row, scrollable, slider, text, text_input, tooltip,
+    },
+    window,
 };
 
 use iced_table::table;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
+
+use alerts::{Alert, AlertState};
+use args::Args;
+use chrono::{DateTime, Utc};
+use clock::{Clock, SystemClock};
+use config::{ColumnLayout, Config, LaunchOverrides};
+use filters::Filters;
+use flash::Flash;
+use format::{ChangeDisplay, NumberStyle, Precision, Separator};
+use geometry::WindowGeometry;
+use grouping::{Group, Grouping, TableRow};
+use histogram::Histogram;
+use row_action::RowAction;
+use rules::{Comparator, Rule, RuleColor};
+use sort::SortDirection;
+use sparkline::Sparkline;
+use summary::{QuoteCurrency, RankTag, Rates, summarize};
+use theme::{Density, ThemeChoice, ThemeMode};
+use transform::Transform;
+
+use krader::data::{self, FetchError, WatchItem, http_client, tickers_url};
+use krader::feed;
+use krader::source::{self, DataSource};
+
+const GREEN: Color = Color::from_rgb(0.0, 1.0, 0.0);
+const YELLOW: Color = Color::from_rgb(1.0, 0.8, 0.0);
+const RED: Color = Color::from_rgb(1.0, 0.2, 0.2);
+const GREY: Color = Color::from_rgb(0.6, 0.6, 0.6);
+const CROSSED_BACKGROUND: Color = Color::from_rgba(1.0, 0.8, 0.0, 0.2);
+const SELECTED_BACKGROUND: Color = Color::from_rgba(0.2, 0.5, 1.0, 0.25);
+const GAINER_BACKGROUND: Color = Color::from_rgba(0.0, 0.8, 0.3, 0.35);
+const LOSER_BACKGROUND: Color = Color::from_rgba(0.9, 0.2, 0.2, 0.35);
+
+/// The polling periods offered in the status row.
+const REFRESH_INTERVALS: [RefreshInterval; 4] = [
+    RefreshInterval(Duration::from_secs(1)),
+    RefreshInterval(Duration::from_secs(5)),
+    RefreshInterval(Duration::from_secs(15)),
+    RefreshInterval(Duration::from_secs(60)),
+];
+/// Data is flagged as stale once this many polls in a row have brought nothing new.
+const STALE_AFTER_POLLS: u32 = 3;
+
+/// How often REST snapshots are still fetched while the live feed is up, for the fields the
+/// feed doesn't carry and markets listed since it connected.
+const LIVE_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often live updates are folded into the displayed rows, so a burst of updates costs one
+/// re-sort rather than one each.
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+/// The frames of the loading spinner, and how long each is shown.
+const SPINNER: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+/// Delay before the first retry of the initial load, doubling with each further attempt.
+const FIRST_LOAD_BACKOFF: Duration = Duration::from_millis(500);
+/// The choices offered for `Config::outer_padding`; 0 fills the window edge to edge.
+const OUTER_PADDINGS: [u16; 5] = [0, 5, 10, 20, 40];
+/// The choices offered for `Config::highlight_movers`, and the one turning it on picks.
+const MOVER_COUNTS: [usize; 4] = [3, 5, 10, 20];
+const DEFAULT_MOVER_COUNT: usize = 5;
+/// The choices offered for `Config::max_live_markets`.
+const LIVE_MARKET_LIMITS: [usize; 5] = [25, 50, 100, 200, 500];
+/// The choices offered for `Config::wide_spread_threshold`.
+const SPREAD_THRESHOLDS: [SpreadThreshold; 4] = [
+    SpreadThreshold(0.001),
+    SpreadThreshold(0.0025),
+    SpreadThreshold(0.005),
+    SpreadThreshold(0.01),
+];
+/// The choices offered for `Config::abbrev_decimals`.
+const ABBREV_DECIMALS: [usize; 4] = [0, 1, 2, 3];
+/// The top of the minimum volume slider, as a power of ten.
+const MAX_VOLUME_EXPONENT: f64 = 9.0;
+/// How long typing must pause before the search box filters the rows.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+/// How long the window must stay put before its size and position are saved.
+const WINDOW_SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Perpetuals fund hourly, so a rate recurs this many times a year.
+const FUNDINGS_PER_YEAR: f64 = 24.0 * 365.0;
+/// How long a cell stays highlighted after its value changes.
+const FLASH_DURATION: Duration = Duration::from_millis(500);
+/// Sizes ranked at least this high among the displayed ones are also shown in bold.
+const LARGE_SIZE_RANK: f64 = 0.9;
+/// A sort column and its direction.
+type Sort = (ColumnKind, SortDirection);
+
+/// Width of the market detail panel beside the table.
+const DETAIL_WIDTH: f32 = 300.0;
+
+/// Rough glyph widths of the default and monospace fonts at the normal text size, for autofit.
+const AVERAGE_GLYPH_WIDTH: f32 = 8.5;
+const MONOSPACE_GLYPH_WIDTH: f32 = 9.6;
+/// Cell padding on both sides plus a little breathing room.
+const AUTOFIT_PADDING: f32 = 16.0;
+const AUTOFIT_MAX_WIDTH: f32 = 480.0;
+/// The widest a column can be dragged, well past any real cell but short of losing the table.
+const MAX_COLUMN_WIDTH: f32 = 1000.0;
+/// The longest gap between two clicks on a row or header that still counts as a double click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Relative spreads up to this many basis points are considered tight.
+const TIGHT_SPREAD_BPS: f64 = 5.0;
+/// Relative spreads above this many basis points are considered wide.
+const WIDE_SPREAD_BPS: f64 = 25.0;
 
 fn main() -> iced::Result {
+    logger::init();
+    let args = match Args::parse(std::env::args_os().skip(1)) {
+        Ok(args) if args.help => {
+            println!("{}", args::USAGE);
+            return Ok(());
+        }
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("krader: {e}\n{}", args::USAGE);
+            std::process::exit(2);
+        }
+    };
+    config::init_dir(args.config_dir.clone());
+    let config = Config::load();
+
     application(Krader::title, Krader::update, Krader::view)
         .subscription(Krader::subscription)
         .theme(Krader::theme)
-        .run_with(Krader::new)
+        .window(
+            config
+                .window
+                .map(WindowGeometry::settings)
+                .unwrap_or_default(),
+        )
+        .run_with(move || Krader::new(args, config))
 }
 
 pub struct Krader {
     columns: Vec<WatchlistColumn>,
     watch_list: Vec<WatchItem>,
+    /// The rows of `watch_list` that pass `filters`, in display order.
+    rows: Vec<WatchItem>,
+    filters: Filters,
+    /// Why the allowlist file couldn't be read, if it couldn't.
+    allowlist_error: Option<String>,
+    /// `rows` laid out under group headers, which is what the table renders.
+    table_rows: Vec<TableRow>,
+    collapsed_groups: HashSet<String>,
+    sort: Option<Sort>,
+    /// Symbols in the order captured by "Freeze order", overriding `sort` until unfrozen.
+    frozen_order: Option<Vec<String>>,
+    /// Distribution of 24h changes across `rows`.
+    histogram: Histogram,
+    show_histogram: bool,
     header: scrollable::Id,
     body: scrollable::Id,
     footer: scrollable::Id,
-    resize_columns_enabled: bool,
-    footer_enabled: bool,
-    min_width_enabled: bool,
+    /// The scrollables of the separate table holding the first column while it's pinned.
+    pinned_header: scrollable::Id,
+    pinned_body: scrollable::Id,
+    pinned_footer: scrollable::Id,
+    /// The container around the table, to find where it is when taking a snapshot.
+    table_container: container::Id,
+    /// Where the main table body is scrolled to, so the pinned body can follow it vertically.
+    body_offset: scrollable::AbsoluteOffset,
+    column_menu: Option<ColumnMenu>,
+    /// The rule being composed while the rules editor is open.
+    rule_draft: Option<RuleDraft>,
+    /// The alert being entered, while the alerts editor is open.
+    alert_draft: Option<AlertDraft>,
+    /// What each of `config.alerts` last saw, in the same order.
+    alert_states: Vec<AlertState>,
+    summary_currency: QuoteCurrency,
+    rates: Rates,
+    /// Each market's recent mark prices, for the sparkline column.
+    history: sparkline::History,
+    config: Config,
+    toast: Option<Toast>,
+    /// The search box as typed; it reaches `filters` once typing pauses.
+    search_input: String,
+    search_box: text_input::Id,
+    /// Shows the keyboard shortcut overlay.
+    show_help: bool,
+    /// Bumped on every keystroke in the search box, so only the latest one applies the search.
+    search_generation: u64,
+    /// Bumped on every window move or resize, so only the last of a burst saves the config.
+    window_generation: u64,
+    /// Symbol of the row whose context menu is open.
+    row_menu: Option<String>,
+    /// The market whose detail panel is open.
+    detail: Option<String>,
+    /// The row and time of the last click, to tell double clicks apart.
+    last_click: Option<(String, DateTime<Utc>)>,
+    /// The last header clicked, when, and the sort from before that click.
+    last_header_click: Option<(ColumnKind, DateTime<Utc>, Option<Sort>)>,
+    /// Hides the biggest mover banner until it is toggled back on.
+    mover_dismissed: bool,
+    /// The command palette query, while the palette is open.
+    palette: Option<String>,
+    palette_input: text_input::Id,
+    show_settings: bool,
+    show_column_picker: bool,
+    /// The OS light/dark preference, as last detected.
+    system_theme: ThemeMode,
+    load_state: LoadState,
+    /// Advances while the first load is in flight, to animate the spinner.
+    spinner_frame: usize,
+    /// Stop polling so the table holds still while it's being read.
+    is_paused: bool,
+    /// When tickers last arrived.
+    last_update: Option<DateTime<Utc>>,
+    /// Why the latest fetch failed, until one succeeds or the banner is dismissed.
+    last_error: Option<String>,
+    /// Fetches that failed in a row since the last one that succeeded.
+    consecutive_failures: u32,
+    /// The slowest Kraken last asked us to poll at, until a fetch gets through again.
+    rate_limit: Option<Duration>,
+    clock: Box<dyn Clock>,
+    /// Serve the bundled sample tickers instead of calling Kraken.
+    mock_data: bool,
+    client: reqwest::Client,
+    /// Where futures tickers are fetched from, built from the validated endpoint.
+    tickers_url: String,
+    /// The WebSocket feed of the same endpoint.
+    feed_url: String,
+    /// Where to send the symbols to follow, while the live feed is connected.
+    feed: Option<futures::channel::mpsc::UnboundedSender<Vec<String>>>,
+    /// How many markets the live feed was last asked to follow.
+    live_markets: usize,
+    /// Live updates arrived that the displayed rows don't show yet.
+    live_changed: bool,
+    /// How many times the live feed has reconnected, for the status bar.
+    feed_reconnects: u32,
+    /// The live feed kept failing to connect, so tickers are polled until it's turned back on.
+    feed_failed: bool,
+    fetches: Fetches,
+}
+
+/// Numbers fetches, so a response overtaken by a newer request is recognised and dropped.
+#[derive(Debug, Default)]
+struct Fetches {
+    latest: u64,
+    /// The latest fetch is under way; polls are skipped until it completes.
+    in_flight: bool,
+}
+
+impl Fetches {
+    /// Starts a fetch, superseding any still under way, and returns its number.
+    fn start(&mut self) -> u64 {
+        self.latest += 1;
+        self.in_flight = true;
+        self.latest
+    }
+
+    /// Ends fetch `id`. Returns false if a newer fetch has started since, making its result stale.
+    fn finish(&mut self, id: u64) -> bool {
+        if id != self.latest {
+            return false;
+        }
+        self.in_flight = false;
+        true
+    }
+}
+
+/// Progress of getting the first data on screen.
+#[derive(Debug, Clone, PartialEq)]
+enum LoadState {
+    Loading,
+    /// An earlier attempt at the first fetch failed; `attempt` is the one now under way.
+    Retrying {
+        attempt: u32,
+        error: String,
+    },
+    /// The first fetch failed before any data arrived. Polling carries on until one succeeds.
+    Failed(String),
+    Loaded,
+}
+
+/// A polling period, as listed in the refresh interval picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RefreshInterval(Duration);
+
+impl fmt::Display for RefreshInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "every {}s", self.0.as_secs())
+    }
+}
+
+/// A relative spread, as listed in the wide spread picker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SpreadThreshold(f64);
+
+impl fmt::Display for SpreadThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format::percent(self.0))
+    }
+}
+
+/// A short-lived notice shown in the status row.
+#[derive(Debug, Clone)]
+struct Toast {
+    id: u64,
+    message: String,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     SyncHeader(scrollable::AbsoluteOffset),
+    SyncPinned(scrollable::AbsoluteOffset),
     Resizing(usize, f32),
     Resized,
     FetchData,
-    DataFetched(Result<Vec<WatchItem>, String>),
+    Tick,
+    IntervalChanged(Duration),
+    DataSourceChanged(DataSource),
+    DismissError,
+    DataFetched(u64, Result<Vec<WatchItem>, String>),
+    RateLimited(u64, Duration),
+    FlashFrame(Instant),
+    SpinnerTick,
+    TogglePause,
+    ToggleFavorite(String),
+    FlashFieldToggled(ColumnKind, bool),
+    SummaryCurrencyChanged(QuoteCurrency),
+    ToggleSettings,
+    ToggleColumnPicker,
+    ToggleColumn(ColumnKind),
+    ToggleHistogram,
+    MonospaceNumbersToggled(bool),
+    HighlightCrossedToggled(bool),
+    WholeVolumesToggled(bool),
+    AbbreviateVolumesToggled(bool),
+    AbbrevDecimalsChanged(usize),
+    HighlightMoversChanged(Option<usize>),
+    WideSpreadThresholdChanged(f64),
+    ChangeDisplayChanged(ChangeDisplay),
+    OuterPaddingChanged(u16),
+    CenterContentToggled(bool),
+    PinFirstColumnToggled(bool),
+    ResizableColumnsToggled(bool),
+    FooterToggled(bool),
+    FillWidthToggled(bool),
+    CellTooltipsToggled(bool),
+    ZebraStripesToggled(bool),
+    CompactRowsToggled(bool),
+    MergeQuoteToggled(bool),
+    ColorSizesToggled(bool),
+    CrossedOnlyToggled(bool),
+    HideSuspendedToggled(bool),
+    MinVolumeChanged(f64),
+    SearchChanged(String),
+    FocusSearch,
+    /// Escape outside the palette: closes the help overlay, or else clears the search.
+    Escape,
+    ToggleHelp,
+    ApplySearch(u64),
+    AllowlistOnlyToggled(bool),
+    AllowlistPathChanged(String),
+    ReloadAllowlist,
+    ClearAllFilters,
+    GroupingChanged(Grouping),
+    ToggleGroup(String),
+    OpenPalette,
+    ClosePalette,
+    PaletteQueryChanged(String),
+    /// Runs the best match for the palette query.
+    PaletteSubmitted,
+    RunCommand(Box<Message>),
+    FreezeOrder,
+    UnfreezeOrder,
+    /// Sorts by a column ascending, then descending, then not at all.
+    SortBy(ColumnKind),
+    HeaderClicked(ColumnKind),
+    AutofitColumn(usize),
+    CycleSortColumn,
+    ToggleSortDirection,
+    ToggleColumnMenu(ColumnKind),
+    TransformAbsToggled(bool),
+    TransformMultiplyChanged(String),
+    TransformAddChanged(String),
+    TransformSuffixChanged(String),
+    ResetTransform,
+    PrecisionChanged(ColumnKind, Precision),
+    DecimalsChanged(Precision),
+    NumberStyleChanged(ColumnKind, NumberStyle),
+    SeparatorChanged(Separator),
+    ToggleRulesEditor,
+    RuleDraftChanged(RuleDraft),
+    AddRule,
+    RemoveRule(usize),
+    ToggleAlertsEditor,
+    AlertDraftChanged(AlertDraft),
+    AddAlert,
+    RemoveAlert(usize),
+    CopyTableImage,
+    /// A window screenshot, and the table's bounds within it if it's on screen.
+    TableImageCaptured(window::Screenshot, Option<Rectangle>),
+    TableImageSaved(Result<PathBuf, String>),
+    DismissToast(u64),
+    RowClicked(String),
+    RowClickActionChanged(RowAction),
+    RowDoubleClickActionChanged(RowAction),
+    CloseDetail,
+    SelectRow(String),
+    DismissMoverBanner,
+    MoverBannerToggled(bool),
+    OpenRowMenu(String),
+    CloseRowMenu,
+    ReportRow(String),
+    /// Copies a row's key fields as tab-separated text.
+    CopyRow(String),
+    CopyRowJson(String),
+    CopySelectedRow,
+    ThemeChoiceChanged(ThemeChoice),
+    WindowFocused,
+    WindowResized(iced::Size),
+    WindowMoved(iced::Point),
+    SaveWindowGeometry(u64),
+    UseMockData(bool),
+    Feed(live::Event),
+    /// A market's latest ticker from the live feed, with only the fields the feed carries set.
+    TickerUpdate(Box<WatchItem>),
+    ApplyLiveUpdates,
+    LiveFeedToggled(bool),
+    MaxLiveMarketsChanged(usize),
+}
+
+/// A conditional formatting rule as entered in the rules editor.
+#[derive(Debug, Clone)]
+struct RuleDraft {
+    target: ColumnKind,
+    field: ColumnKind,
+    comparator: Comparator,
+    value: String,
+    color: RuleColor,
+    background: bool,
+}
+
+impl Default for RuleDraft {
+    fn default() -> Self {
+        Self {
+            target: ColumnKind::MarkPrice,
+            field: ColumnKind::Change24h,
+            comparator: Comparator::Greater,
+            value: "10".to_string(),
+            color: RuleColor::Yellow,
+            background: false,
+        }
+    }
+}
+
+impl RuleDraft {
+    fn rule(&self) -> Option<Rule> {
+        Some(Rule {
+            target: self.target,
+            field: self.field,
+            comparator: self.comparator,
+            value: self.value.trim().parse().ok()?,
+            color: self.color,
+            background: self.background,
+        })
+    }
 }
 
-#[derive(Debug, Error)]
-pub enum FetchError {
-    #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+/// A price alert as entered in the alerts editor.
+#[derive(Debug, Clone)]
+struct AlertDraft {
+    symbol: String,
+    field: ColumnKind,
+    comparator: Comparator,
+    value: String,
+}
 
-    #[error("Failed to parse JSON: {0}")]
-    Json(#[from] serde_json::Error),
+impl AlertDraft {
+    fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            field: ColumnKind::MarkPrice,
+            comparator: Comparator::Greater,
+            value: String::new(),
+        }
+    }
 
-    #[error("Price field missing in response")]
-    MissingField,
+    fn alert(&self) -> Option<Alert> {
+        let symbol = self.symbol.trim();
+        Some(Alert {
+            symbol: (!symbol.is_empty()).then(|| symbol.to_string())?,
+            field: self.field,
+            comparator: self.comparator,
+            value: self.value.trim().parse().ok()?,
+        })
+    }
+}
 
-    #[error("Failed to parse price string: {0}")]
-    Parse(#[from] std::num::ParseFloatError),
+/// The open per-column menu, holding the transform fields as typed.
+#[derive(Debug, Clone)]
+struct ColumnMenu {
+    kind: ColumnKind,
+    multiply: String,
+    add: String,
 }
 
 impl Krader {
-    fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                columns: vec![
-                    WatchlistColumn::new(ColumnKind::Pair),
-                    WatchlistColumn::new(ColumnKind::MarkPrice),
-                    WatchlistColumn::new(ColumnKind::Vol24h),
-                    WatchlistColumn::new(ColumnKind::VolumeQuote),
-                    WatchlistColumn::new(ColumnKind::Symbol),
-                    WatchlistColumn::new(ColumnKind::Last),
-                    WatchlistColumn::new(ColumnKind::LastTime),
-                    WatchlistColumn::new(ColumnKind::Tag),
-                    WatchlistColumn::new(ColumnKind::Bid),
-                    WatchlistColumn::new(ColumnKind::BidSize),
-                    WatchlistColumn::new(ColumnKind::Ask),
-                    WatchlistColumn::new(ColumnKind::AskSize),
-                    WatchlistColumn::new(ColumnKind::OpenInterest),
-                    WatchlistColumn::new(ColumnKind::Open24h),
-                    WatchlistColumn::new(ColumnKind::High24h),
-                    WatchlistColumn::new(ColumnKind::Low24h),
-                    WatchlistColumn::new(ColumnKind::LastSize),
-                    WatchlistColumn::new(ColumnKind::FundingRate),
-                    WatchlistColumn::new(ColumnKind::FundingRatePrediction),
-                    WatchlistColumn::new(ColumnKind::Suspended),
-                    WatchlistColumn::new(ColumnKind::IndexPrice),
-                    WatchlistColumn::new(ColumnKind::PostOnly),
-                    WatchlistColumn::new(ColumnKind::Change24h),
-                ],
-                watch_list: vec![],
-                header: scrollable::Id::unique(),
-                body: scrollable::Id::unique(),
-                footer: scrollable::Id::unique(),
-                resize_columns_enabled: true,
-                footer_enabled: true,
-                min_width_enabled: true,
-            },
-            Task::perform(
-                async { fetch_data().await.map_err(|e| e.to_string()) },
-                Message::DataFetched,
-            ),
-        )
+    fn new(args: Args, mut config: Config) -> (Self, Task<Message>) {
+        config.launch = LaunchOverrides {
+            refresh_interval: args.interval,
+            theme: args.theme,
+        };
+        let options = CellOptions::from_config(&config);
+        let endpoint = args.endpoint.unwrap_or_else(|| config.endpoint.clone());
+        let client = http_client(config.request_timeout);
+        let mut krader = Self {
+            columns: WatchlistColumn::all_from_config(&config, &options),
+            watch_list: vec![],
+            rows: vec![],
+            filters: Filters::default(),
+            allowlist_error: None,
+            table_rows: vec![],
+            collapsed_groups: HashSet::new(),
+            sort: config.sort,
+            frozen_order: None,
+            histogram: Histogram::default(),
+            show_histogram: true,
+            header: scrollable::Id::unique(),
+            body: scrollable::Id::unique(),
+            footer: scrollable::Id::unique(),
+            pinned_header: scrollable::Id::unique(),
+            pinned_body: scrollable::Id::unique(),
+            pinned_footer: scrollable::Id::unique(),
+            table_container: container::Id::unique(),
+            body_offset: scrollable::AbsoluteOffset::default(),
+            column_menu: None,
+            rule_draft: None,
+            alert_draft: None,
+            alert_states: Vec::new(),
+            summary_currency: QuoteCurrency::Usd,
+            rates: Rates::default(),
+            history: sparkline::History::new(),
+            config,
+            toast: None,
+            search_input: String::new(),
+            search_box: text_input::Id::unique(),
+            show_help: false,
+            search_generation: 0,
+            window_generation: 0,
+            row_menu: None,
+            detail: None,
+            last_click: None,
+            last_header_click: None,
+            mover_dismissed: false,
+            palette: None,
+            palette_input: text_input::Id::unique(),
+            show_settings: false,
+            show_column_picker: false,
+            system_theme: ThemeMode::system(),
+            load_state: LoadState::Loading,
+            spinner_frame: 0,
+            is_paused: false,
+            last_update: None,
+            last_error: None,
+            consecutive_failures: 0,
+            rate_limit: None,
+            clock: Box::new(SystemClock),
+            mock_data: false,
+            client,
+            tickers_url: tickers_url(&endpoint),
+            feed_url: feed::feed_url(&endpoint),
+            feed: None,
+            live_markets: 0,
+            live_changed: false,
+            feed_reconnects: 0,
+            feed_failed: false,
+            fetches: Fetches::default(),
+        };
+        krader.reload_allowlist();
+        krader.sort_changed();
+
+        (krader, Task::done(Message::FetchData))
     }
 
+    /// The app name with how many markets are shown, or why none are.
     fn title(&self) -> String {
-        "🦑 Krader".into()
+        let status = if self.last_error.is_some() {
+            "offline".to_string()
+        } else if self.load_state != LoadState::Loaded {
+            "loading…".to_string()
+        } else if self.rows.len() == self.watch_list.len() {
+            format!("{} markets", self.rows.len())
+        } else {
+            format!("{} of {} markets", self.rows.len(), self.watch_list.len())
+        };
+
+        format!("🦑 Krader — {status}")
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::SyncHeader(offset) => Task::batch(vec![
-                scrollable::scroll_to(self.header.clone(), offset),
-                scrollable::scroll_to(self.footer.clone(), offset),
-            ]),
+            Message::SyncHeader(offset) => {
+                self.body_offset = offset;
+                let mut tasks = vec![
+                    scrollable::scroll_to(self.header.clone(), offset),
+                    scrollable::scroll_to(self.footer.clone(), offset),
+                ];
+                if self.config.pin_first_column {
+                    tasks.push(scrollable::scroll_to(
+                        self.pinned_body.clone(),
+                        scrollable::AbsoluteOffset { x: 0.0, ..offset },
+                    ));
+                }
+                Task::batch(tasks)
+            }
+            // Scrolling over the pinned column moves the rest of the rows along with it.
+            Message::SyncPinned(offset) => scrollable::scroll_to(
+                self.body.clone(),
+                scrollable::AbsoluteOffset {
+                    y: offset.y,
+                    ..self.body_offset
+                },
+            ),
             Message::Resizing(index, offset) => {
                 if let Some(column) = self.columns.get_mut(index) {
-                    column.resize_offset = Some(offset);
+                    // Clamped during the drag too, so the preview never shows a width it won't keep.
+                    column.resize_offset =
+                        Some(column.clamp_width(column.width + offset) - column.width);
                 }
                 Task::none()
             }
             Message::Resized => {
                 self.columns.iter_mut().for_each(|column| {
                     if let Some(offset) = column.resize_offset.take() {
-                        column.width += offset;
+                        column.width = column.clamp_width(column.width + offset);
                     }
                 });
+                self.store_layout();
+                self.config_changed();
                 Task::none()
             }
-            Message::FetchData => Task::perform(
-                async { fetch_data().await.map_err(|e| e.to_string()) },
-                Message::DataFetched,
-            ),
-            Message::DataFetched(Ok(watch_list)) => {
-                self.watch_list = watch_list;
+            Message::Tick => {
+                let now = self.clock.now();
+                for column in &mut self.columns {
+                    column.now = now;
+                    // Ages are the only text that changes with time alone.
+                    if column.kind == ColumnKind::LastTime {
+                        column.refresh_texts(&self.rows);
+                    }
+                }
                 Task::none()
             }
-            Message::DataFetched(Err(e)) => {
-                eprintln!("{e}");
+            Message::DataSourceChanged(source) => {
+                if source == self.config.data_source {
+                    return Task::none();
+                }
+                self.config.switch_data_source(source);
+                self.config_changed();
+                // The two sources share no markets, so start over as on launch.
+                self.watch_list.clear();
+                self.history.clear();
+                for column in &mut self.columns {
+                    column.flashing.clear();
+                }
+                self.alert_states.clear();
+                self.last_update = None;
+                self.last_error = None;
+                self.load_state = LoadState::Loading;
+                self.refresh_rows();
+                self.start_fetch()
+            }
+            Message::IntervalChanged(interval) => {
+                self.config.refresh_interval = interval;
+                self.config.launch.refresh_interval = None;
+                self.config_changed();
                 Task::none()
             }
-        }
-    }
-
-    fn view(&self) -> Element<Message> {
-        let table = responsive(|size| {
-            let mut table = table(
-                self.header.clone(),
-                self.body.clone(),
-                &self.columns,
-                &self.watch_list,
-                Message::SyncHeader,
-            );
+            Message::DismissError => {
+                self.last_error = None;
+                Task::none()
+            }
+            Message::FetchData => {
+                // A slow response must not pile up overlapping requests behind it.
+                if self.fetches.in_flight {
+                    return Task::none();
+                }
+                self.start_fetch()
+            }
+            Message::RateLimited(id, _) | Message::DataFetched(id, _)
+                if id != self.fetches.latest =>
+            {
+                Task::none()
+            }
+            Message::RateLimited(id, retry_after) => {
+                self.rate_limit = Some(retry_after);
+                let error = FetchError::RateLimited { retry_after }.to_string();
+                self.update(Message::DataFetched(id, Err(error)))
+            }
+            Message::DataFetched(id, Ok(watch_list)) => {
+                self.fetches.finish(id);
+                if self.consecutive_failures > 0 {
+                    log::info!(
+                        "Fetching recovered after {} failed attempts",
+                        self.consecutive_failures
+                    );
+                }
+                self.load_state = LoadState::Loaded;
+                self.last_update = Some(self.clock.now());
+                self.last_error = None;
+                self.consecutive_failures = 0;
+                self.rate_limit = None;
+                self.rates = Rates::from_tickers(&watch_list);
+                sparkline::record(&mut self.history, &watch_list);
+                let started = Instant::now();
+                for column in &mut self.columns {
+                    column.now = self.last_update.unwrap_or_default();
+                    if self.config.flash_fields.contains(&column.kind) {
+                        let changes = flash::changes(column.kind, &self.watch_list, &watch_list);
+                        column.flashing.extend(
+                            changes
+                                .into_iter()
+                                .map(|(symbol, rose)| (symbol, Flash { rose, started })),
+                        );
+                        column.frame = started;
+                    }
+                }
+                merge_tickers(&mut self.watch_list, watch_list);
+                self.refresh_rows();
+                self.check_alerts()
+            }
+            Message::ToggleFavorite(symbol) => {
+                if !self.config.favorites.remove(&symbol) {
+                    self.config.favorites.insert(symbol);
+                }
+                self.config_changed();
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::TogglePause => {
+                self.is_paused = !self.is_paused;
+                if self.is_paused {
+                    Task::none()
+                } else {
+                    Task::done(Message::FetchData)
+                }
+            }
+            Message::SpinnerTick => {
+                self.spinner_frame = self.spinner_frame.wrapping_add(1);
+                Task::none()
+            }
+            Message::FlashFrame(now) => {
+                for column in &mut self.columns {
+                    column.frame = now;
+                    column
+                        .flashing
+                        .retain(|_, flash| flash.strength(now, FLASH_DURATION) > 0.0);
+                }
+                Task::none()
+            }
+            Message::FlashFieldToggled(kind, enabled) => {
+                self.config.flash_fields.retain(|field| *field != kind);
+                if enabled {
+                    self.config.flash_fields.push(kind);
+                }
+                self.config_changed();
+                Task::none()
+            }
+            Message::DataFetched(id, Err(e)) => {
+                self.fetches.finish(id);
+                log::warn!("Fetch failed: {e}");
+                self.last_error = Some(e.clone());
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+                let failed_attempt = match self.load_state {
+                    LoadState::Loading => 1,
+                    LoadState::Retrying { attempt, .. } => attempt,
+                    LoadState::Failed(_) => {
+                        self.load_state = LoadState::Failed(e);
+                        return Task::none();
+                    }
+                    LoadState::Loaded => return Task::none(),
+                };
 
-            if self.resize_columns_enabled {
-                table = table.on_column_resize(Message::Resizing, Message::Resized);
+                if failed_attempt < self.config.first_load_attempts {
+                    self.load_state = LoadState::Retrying {
+                        attempt: failed_attempt + 1,
+                        error: e,
+                    };
+                    let delay = (FIRST_LOAD_BACKOFF * 2u32.saturating_pow(failed_attempt - 1))
+                        .max(self.rate_limit.unwrap_or_default());
+                    Task::perform(tokio::time::sleep(delay), |_| Message::FetchData)
+                } else {
+                    self.load_state = LoadState::Failed(e);
+                    Task::none()
+                }
             }
-            if self.footer_enabled {
-                table = table.footer(self.footer.clone());
+            Message::UseMockData(enabled) => {
+                self.mock_data = enabled;
+                // Whatever the other source still has in flight would land on top of this one.
+                self.start_fetch()
+            }
+            Message::Feed(live::Event::Connected {
+                markets,
+                reconnects,
+            }) => {
+                self.feed = Some(markets);
+                self.feed_reconnects = reconnects;
+                self.follow_markets();
+                Task::none()
+            }
+            Message::Feed(live::Event::Disconnected) => {
+                // Polling picks up at the normal interval until the feed is back.
+                self.feed = None;
+                Task::none()
+            }
+            Message::Feed(live::Event::GaveUp) => {
+                self.feed = None;
+                self.feed_failed = true;
+                self.show_toast("Live updates failed, polling instead".to_string())
+            }
+            Message::TickerUpdate(update) => {
+                if !self.is_live() {
+                    return Task::none();
+                }
+                // Markets listed since the last snapshot wait for the next one.
+                let Some(item) = self
+                    .watch_list
+                    .iter_mut()
+                    .find(|item| item.symbol.is_some() && item.symbol == update.symbol)
+                else {
+                    return Task::none();
+                };
+                let mut updated = item.clone();
+                updated.merge(*update);
+                let started = Instant::now();
+                for column in &mut self.columns {
+                    if self.config.flash_fields.contains(&column.kind) {
+                        let changes = flash::changes(
+                            column.kind,
+                            std::slice::from_ref(item),
+                            std::slice::from_ref(&updated),
+                        );
+                        column.flashing.extend(
+                            changes
+                                .into_iter()
+                                .map(|(symbol, rose)| (symbol, Flash { rose, started })),
+                        );
+                        column.frame = started;
+                    }
+                }
+                *item = updated;
+                self.last_update = Some(self.clock.now());
+                self.live_changed = true;
+                Task::none()
+            }
+            Message::ApplyLiveUpdates => {
+                if !self.live_changed {
+                    return Task::none();
+                }
+                self.live_changed = false;
+                self.rates = Rates::from_tickers(&self.watch_list);
+                for column in &mut self.columns {
+                    column.now = self.last_update.unwrap_or_default();
+                }
+                self.refresh_rows();
+                self.check_alerts()
+            }
+            Message::LiveFeedToggled(enabled) => {
+                self.config.live_feed = enabled;
+                self.feed_failed = false;
+                if !enabled {
+                    self.feed = None;
+                }
+                self.config_changed();
+                Task::none()
+            }
+            Message::MaxLiveMarketsChanged(limit) => {
+                self.config.max_live_markets = limit;
+                self.config_changed();
+                self.follow_markets();
+                Task::none()
             }
-            if self.min_width_enabled {
-                table = table.min_width(size.width);
+            Message::SummaryCurrencyChanged(currency) => {
+                self.summary_currency = currency;
+                Task::none()
+            }
+            Message::ToggleHistogram => {
+                self.show_histogram = !self.show_histogram;
+                Task::none()
+            }
+            Message::ToggleColumnPicker => {
+                self.show_column_picker = !self.show_column_picker;
+                Task::none()
+            }
+            Message::ToggleColumn(kind) => {
+                if !self.config.hidden_columns.remove(&kind) {
+                    // Hiding every column would leave nothing to click to bring them back.
+                    if self.columns.len() <= 1 {
+                        return Task::none();
+                    }
+                    self.config.hidden_columns.insert(kind);
+                }
+                self.config_changed();
+                self.rebuild_columns();
+                self.sort_changed();
+                Task::none()
+            }
+            Message::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                Task::none()
+            }
+            Message::MonospaceNumbersToggled(enabled) => {
+                self.config.monospace_numbers = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::HighlightCrossedToggled(enabled) => {
+                self.config.highlight_crossed = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::AbbreviateVolumesToggled(enabled) => {
+                self.config.abbreviate_volumes = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::OuterPaddingChanged(padding) => {
+                self.config.outer_padding = padding;
+                self.config_changed();
+                Task::none()
+            }
+            Message::PinFirstColumnToggled(enabled) => {
+                self.config.pin_first_column = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::ResizableColumnsToggled(enabled) => {
+                self.config.resizable_columns = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::FooterToggled(enabled) => {
+                self.config.show_footer = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::FillWidthToggled(enabled) => {
+                self.config.fill_width = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::CenterContentToggled(enabled) => {
+                self.config.center_content = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::ChangeDisplayChanged(display) => {
+                self.config.change_display = display;
+                self.config_changed();
+                Task::none()
+            }
+            Message::HighlightMoversChanged(count) => {
+                self.config.highlight_movers = count;
+                self.config_changed();
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::WideSpreadThresholdChanged(threshold) => {
+                self.config.wide_spread_threshold = threshold;
+                self.config_changed();
+                Task::none()
+            }
+            Message::AbbrevDecimalsChanged(decimals) => {
+                self.config.abbrev_decimals = decimals;
+                self.config_changed();
+                Task::none()
+            }
+            Message::WholeVolumesToggled(enabled) => {
+                self.config.whole_volumes = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::ZebraStripesToggled(enabled) => {
+                self.config.zebra_stripes = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::CompactRowsToggled(enabled) => {
+                self.config.density = if enabled {
+                    Density::Compact
+                } else {
+                    Density::Normal
+                };
+                self.config_changed();
+                Task::none()
+            }
+            Message::CellTooltipsToggled(enabled) => {
+                self.config.cell_tooltips = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::ColorSizesToggled(enabled) => {
+                self.config.color_sizes = enabled;
+                self.config_changed();
+                Task::none()
+            }
+            Message::MergeQuoteToggled(enabled) => {
+                self.config.merge_quote = enabled;
+                self.config_changed();
+                self.rebuild_columns();
+                self.sort_changed();
+                Task::none()
+            }
+            Message::SearchChanged(query) => {
+                self.search_input = query;
+                self.search_generation += 1;
+                let generation = self.search_generation;
+                Task::perform(tokio::time::sleep(SEARCH_DEBOUNCE), move |_| {
+                    Message::ApplySearch(generation)
+                })
+            }
+            Message::ApplySearch(generation) => {
+                if generation == self.search_generation {
+                    self.filters.search_query.clone_from(&self.search_input);
+                    self.refresh_rows();
+                }
+                Task::none()
+            }
+            Message::FocusSearch => text_input::focus(self.search_box.clone()),
+            Message::Escape => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if !self.search_input.is_empty() || !self.filters.search_query.is_empty() {
+                    self.search_input.clear();
+                    self.search_generation += 1;
+                    self.filters.search_query.clear();
+                    self.refresh_rows();
+                }
+                Task::none()
+            }
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+                Task::none()
+            }
+            Message::CrossedOnlyToggled(enabled) => {
+                self.filters.crossed_only = enabled;
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::MinVolumeChanged(volume) => {
+                self.filters.min_volume = volume;
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::HideSuspendedToggled(enabled) => {
+                self.filters.hide_suspended = enabled;
+                self.refresh_rows();
+                Task::none()
             }
+            Message::AllowlistOnlyToggled(enabled) => {
+                self.config.allowlist_only = enabled;
+                self.config_changed();
+                self.reload_allowlist();
+                Task::none()
+            }
+            Message::AllowlistPathChanged(path) => {
+                self.config.allowlist_path = path;
+                Task::none()
+            }
+            Message::ReloadAllowlist => {
+                self.config_changed();
+                self.reload_allowlist();
+                Task::none()
+            }
+            Message::ClearAllFilters => {
+                self.filters = Filters::default();
+                self.search_input.clear();
+                if self.config.allowlist_only {
+                    self.config.allowlist_only = false;
+                    self.config_changed();
+                }
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::GroupingChanged(grouping) => {
+                self.config.grouping = grouping;
+                self.collapsed_groups.clear();
+                self.config_changed();
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::ToggleGroup(key) => {
+                if !self.collapsed_groups.remove(&key) {
+                    self.collapsed_groups.insert(key);
+                }
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::OpenPalette => {
+                self.palette = Some(String::new());
+                text_input::focus(self.palette_input.clone())
+            }
+            Message::ClosePalette => {
+                self.palette = None;
+                Task::none()
+            }
+            Message::PaletteQueryChanged(query) => {
+                self.palette = Some(query);
+                Task::none()
+            }
+            Message::PaletteSubmitted => {
+                let Some(query) = &self.palette else {
+                    return Task::none();
+                };
+                match palette::search(palette::commands(self), query)
+                    .into_iter()
+                    .next()
+                {
+                    Some(command) => self.update(Message::RunCommand(Box::new(command.message))),
+                    None => Task::none(),
+                }
+            }
+            Message::RunCommand(message) => {
+                self.palette = None;
+                self.update(*message)
+            }
+            Message::FreezeOrder => {
+                self.frozen_order = Some(
+                    self.rows
+                        .iter()
+                        .filter_map(|item| item.symbol.clone())
+                        .collect(),
+                );
+                Task::none()
+            }
+            Message::UnfreezeOrder => {
+                self.frozen_order = None;
+                self.refresh_rows();
+                Task::none()
+            }
+            Message::SortBy(kind) => {
+                self.sort = match self.sort {
+                    Some((current, SortDirection::Ascending)) if current == kind => {
+                        Some((kind, SortDirection::Descending))
+                    }
+                    Some((current, SortDirection::Descending)) if current == kind => None,
+                    _ => Some((kind, SortDirection::Ascending)),
+                };
+                self.frozen_order = None;
+                self.sort_changed();
+                Task::none()
+            }
+            Message::HeaderClicked(kind) => {
+                let now = self.clock.now();
+                let double = self.last_header_click.filter(|(last, at, _)| {
+                    *last == kind
+                        && (now - *at)
+                            .to_std()
+                            .is_ok_and(|gap| gap <= DOUBLE_CLICK_INTERVAL)
+                });
+                let Some((_, _, sort_before)) = double else {
+                    self.last_header_click = Some((kind, now, self.sort));
+                    return self.update(Message::SortBy(kind));
+                };
 
-            table.into()
-        });
-        let time_status = iced::widget::Row::new()
-            .height(24)
-            .padding(5)
-            .push(
-                text("Last update: ")
-                    .size(14)
-                    .color(Color::from_rgb(0.0, 1.0, 0.0)),
-            )
-            .push(
-                text(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string())
-                    .size(14)
-                    .color(Color::from_rgb(0.0, 1.0, 0.0)),
-            );
+                // The first click of the pair sorted; a double click only means to fit the width.
+                self.last_header_click = None;
+                self.sort = sort_before;
+                self.sort_changed();
+                match self.columns.iter().position(|column| column.kind == kind) {
+                    Some(index) => self.update(Message::AutofitColumn(index)),
+                    None => Task::none(),
+                }
+            }
+            Message::AutofitColumn(index) => {
+                if let Some(column) = self.columns.get_mut(index) {
+                    column.width = column.fitted_width();
+                    column.resize_offset = None;
+                    self.store_layout();
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::CycleSortColumn => {
+                let next = next_sort_column(
+                    self.columns.iter().map(|column| column.kind),
+                    self.sort.map(|(kind, _)| kind),
+                );
+                self.sort = next.map(|kind| {
+                    let direction = self.sort.map_or(SortDirection::Ascending, |(_, d)| d);
+                    (kind, direction)
+                });
+                self.sort_changed();
+                Task::none()
+            }
+            Message::ToggleSortDirection => {
+                if let Some((_, direction)) = &mut self.sort {
+                    *direction = direction.toggled();
+                    self.sort_changed();
+                }
+                Task::none()
+            }
+            Message::ToggleColumnMenu(kind) => {
+                self.column_menu = match self.column_menu.take() {
+                    Some(menu) if menu.kind == kind => None,
+                    _ => {
+                        let transform = self.config.transform(kind);
+                        Some(ColumnMenu {
+                            kind,
+                            multiply: transform.multiply.to_string(),
+                            add: transform.add.to_string(),
+                        })
+                    }
+                };
+                Task::none()
+            }
+            Message::TransformAbsToggled(abs) => {
+                self.edit_transform(|transform| transform.abs = abs);
+                Task::none()
+            }
+            Message::TransformMultiplyChanged(input) => {
+                if let Ok(multiply) = input.parse() {
+                    self.edit_transform(|transform| transform.multiply = multiply);
+                }
+                if let Some(menu) = &mut self.column_menu {
+                    menu.multiply = input;
+                }
+                Task::none()
+            }
+            Message::TransformAddChanged(input) => {
+                if let Ok(add) = input.parse() {
+                    self.edit_transform(|transform| transform.add = add);
+                }
+                if let Some(menu) = &mut self.column_menu {
+                    menu.add = input;
+                }
+                Task::none()
+            }
+            Message::TransformSuffixChanged(suffix) => {
+                self.edit_transform(|transform| transform.suffix = suffix);
+                Task::none()
+            }
+            Message::PrecisionChanged(kind, precision) => {
+                if precision == self.config.decimals {
+                    self.config.precision.remove(&kind);
+                } else {
+                    self.config.precision.insert(kind, precision);
+                }
+                self.config_changed();
+                Task::none()
+            }
+            Message::DecimalsChanged(decimals) => {
+                self.config.decimals = decimals;
+                self.config_changed();
+                Task::none()
+            }
+            Message::NumberStyleChanged(kind, style) => {
+                self.config.number_styles.insert(kind, style);
+                self.config_changed();
+                Task::none()
+            }
+            Message::SeparatorChanged(separator) => {
+                self.config.thousands_separator = separator;
+                self.config_changed();
+                Task::none()
+            }
+            Message::ResetTransform => {
+                if let Some(menu) = &mut self.column_menu {
+                    self.config.transforms.remove(&menu.kind);
+                    let transform = Transform::default();
+                    menu.multiply = transform.multiply.to_string();
+                    menu.add = transform.add.to_string();
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::ToggleRulesEditor => {
+                self.rule_draft = match self.rule_draft {
+                    Some(_) => None,
+                    None => Some(RuleDraft::default()),
+                };
+                Task::none()
+            }
+            Message::RuleDraftChanged(draft) => {
+                self.rule_draft = Some(draft);
+                Task::none()
+            }
+            Message::AddRule => {
+                if let Some(rule) = self.rule_draft.as_ref().and_then(RuleDraft::rule) {
+                    self.config.rules.push(rule);
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::RemoveRule(index) => {
+                if index < self.config.rules.len() {
+                    self.config.rules.remove(index);
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::ToggleAlertsEditor => {
+                self.alert_draft = match self.alert_draft {
+                    Some(_) => None,
+                    // Start from the selected market, the likeliest one to watch.
+                    None => Some(AlertDraft::new(
+                        self.columns
+                            .first()
+                            .and_then(|column| column.selected.clone())
+                            .unwrap_or_default(),
+                    )),
+                };
+                Task::none()
+            }
+            Message::AlertDraftChanged(draft) => {
+                self.alert_draft = Some(draft);
+                Task::none()
+            }
+            Message::AddAlert => {
+                if let Some(alert) = self.alert_draft.as_ref().and_then(AlertDraft::alert) {
+                    self.config.alerts.push(alert);
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::RemoveAlert(index) => {
+                if index < self.config.alerts.len() {
+                    self.config.alerts.remove(index);
+                    if index < self.alert_states.len() {
+                        self.alert_states.remove(index);
+                    }
+                    self.config_changed();
+                }
+                Task::none()
+            }
+            Message::CopyTableImage => container::visible_bounds(self.table_container.clone())
+                .then(|bounds| {
+                    window::get_oldest()
+                        .and_then(window::screenshot)
+                        .map(move |screenshot| Message::TableImageCaptured(screenshot, bounds))
+                }),
+            Message::TableImageCaptured(screenshot, bounds) => {
+                let now = self.clock.now();
+                Task::perform(
+                    async move {
+                        // Without bounds the table isn't laid out, so the whole window is kept.
+                        let screenshot = match bounds {
+                            Some(bounds) => snapshot::crop(&screenshot, bounds),
+                            None => Ok(screenshot),
+                        };
+                        screenshot
+                            .and_then(|screenshot| snapshot::save(&screenshot, now))
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::TableImageSaved,
+                )
+            }
+            Message::TableImageSaved(Ok(path)) => {
+                self.show_toast(format!("Snapshot saved to {}", path.display()))
+            }
+            Message::TableImageSaved(Err(e)) => {
+                log::error!("Couldn't save a snapshot: {e}");
+                self.show_toast(e)
+            }
+            Message::RowClicked(symbol) => {
+                let now = self.clock.now();
+                let double = self.last_click.as_ref().is_some_and(|(last, at)| {
+                    *last == symbol
+                        && (now - *at)
+                            .to_std()
+                            .is_ok_and(|gap| gap <= DOUBLE_CLICK_INTERVAL)
+                });
+                // A third click starts a new pair rather than counting as another double click.
+                self.last_click = (!double).then(|| (symbol.clone(), now));
+
+                let action = if double {
+                    self.config.row_double_click_action
+                } else {
+                    self.config.row_click_action
+                };
+                self.run_row_action(action, symbol)
+            }
+            Message::RowClickActionChanged(action) => {
+                self.config.row_click_action = action;
+                self.config_changed();
+                Task::none()
+            }
+            Message::RowDoubleClickActionChanged(action) => {
+                self.config.row_double_click_action = action;
+                self.config_changed();
+                Task::none()
+            }
+            Message::SelectRow(symbol) => {
+                self.select(Some(symbol));
+                Task::none()
+            }
+            Message::DismissMoverBanner => {
+                self.mover_dismissed = true;
+                Task::none()
+            }
+            Message::MoverBannerToggled(enabled) => {
+                self.config.show_mover_banner = enabled;
+                self.mover_dismissed = false;
+                self.config_changed();
+                Task::none()
+            }
+            Message::CloseDetail => {
+                self.detail = None;
+                Task::none()
+            }
+            Message::OpenRowMenu(symbol) => {
+                self.row_menu = Some(symbol);
+                Task::none()
+            }
+            Message::CloseRowMenu => {
+                self.row_menu = None;
+                Task::none()
+            }
+            Message::CopyRow(symbol) => self.copy_row(&symbol, report::row_tsv),
+            Message::CopyRowJson(symbol) => self.copy_row(&symbol, report::row_json),
+            Message::CopySelectedRow => {
+                match self
+                    .columns
+                    .first()
+                    .and_then(|column| column.selected.clone())
+                {
+                    Some(symbol) => self.update(Message::CopyRow(symbol)),
+                    None => Task::none(),
+                }
+            }
+            Message::ReportRow(symbol) => {
+                self.row_menu = None;
+                let Some(item) = self
+                    .watch_list
+                    .iter()
+                    .find(|item| item.symbol.as_ref() == Some(&symbol))
+                else {
+                    return self.show_toast(format!("{symbol} is no longer listed"));
+                };
+
+                Task::batch([
+                    iced::clipboard::write(report::diagnostic_bundle(
+                        item,
+                        if self.mock_data {
+                            "mock"
+                        } else {
+                            self.source_url()
+                        },
+                        self.config.data_source,
+                        self.clock.now(),
+                    )),
+                    self.show_toast(format!("Diagnostic report for {symbol} copied")),
+                ])
+            }
+            Message::ThemeChoiceChanged(choice) => {
+                match choice {
+                    ThemeChoice::System => self.config.follow_system_theme = true,
+                    ThemeChoice::Explicit(mode) => {
+                        self.config.follow_system_theme = false;
+                        self.config.theme = mode;
+                    }
+                }
+                self.config.launch.theme = None;
+                self.config_changed();
+                Task::none()
+            }
+            Message::WindowFocused => {
+                // iced doesn't report OS theme changes, so re-check whenever we regain focus.
+                self.system_theme = ThemeMode::system();
+                Task::none()
+            }
+            Message::WindowResized(size) => self.window_changed(|geometry| {
+                geometry.width = size.width;
+                geometry.height = size.height;
+            }),
+            Message::WindowMoved(position) => {
+                self.window_changed(|geometry| geometry.position = Some((position.x, position.y)))
+            }
+            Message::SaveWindowGeometry(generation) => {
+                if generation == self.window_generation
+                    && let Err(e) = self.config.save()
+                {
+                    log::error!("{e}");
+                }
+                Task::none()
+            }
+            Message::DismissToast(id) => {
+                if self.toast.as_ref().is_some_and(|toast| toast.id == id) {
+                    self.toast = None;
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Records a window move or resize, saving the config once the window settles.
+    fn window_changed(&mut self, change: impl FnOnce(&mut WindowGeometry)) -> Task<Message> {
+        change(
+            self.config
+                .window
+                .get_or_insert_with(WindowGeometry::default),
+        );
+        self.window_generation += 1;
+        let generation = self.window_generation;
+        Task::perform(tokio::time::sleep(WINDOW_SAVE_DELAY), move |_| {
+            Message::SaveWindowGeometry(generation)
+        })
+    }
+
+    /// The tickers URL of the selected data source. The endpoint setting only applies to futures.
+    fn source_url(&self) -> &str {
+        match self.config.data_source {
+            DataSource::Futures => &self.tickers_url,
+            DataSource::Spot => source::SPOT_TICKER_URL,
+        }
+    }
+
+    /// Fetches tickers from the current source, superseding any fetch still under way.
+    fn start_fetch(&mut self) -> Task<Message> {
+        let id = self.fetches.start();
+        if self.mock_data {
+            return Task::done(Message::DataFetched(id, Ok(mock::tickers())));
+        }
+        let client = self.client.clone();
+        Task::perform(
+            fetch_data(
+                client,
+                self.source_url().to_string(),
+                self.config.data_source,
+            ),
+            move |result| match result {
+                Err(FetchError::RateLimited { retry_after }) => {
+                    Message::RateLimited(id, retry_after)
+                }
+                result => Message::DataFetched(id, result.map_err(|e| e.to_string())),
+            },
+        )
+    }
+
+    /// Whether tickers should stream over the live feed, which only carries futures.
+    fn live_feed_wanted(&self) -> bool {
+        self.config.live_feed
+            && !self.feed_failed
+            && self.config.data_source == DataSource::Futures
+            && !self.mock_data
+            && !self.is_paused
+            // REST gives the first snapshot, and with it the markets to follow.
+            && !self.watch_list.is_empty()
+    }
+
+    /// Whether the live feed is connected and updating the rows.
+    fn is_live(&self) -> bool {
+        self.live_feed_wanted() && self.feed.as_ref().is_some_and(|feed| !feed.is_closed())
+    }
+
+    /// Has the live feed follow as many markets as allowed, favorites and displayed rows first.
+    fn follow_markets(&mut self) {
+        let Some(feed) = &self.feed else {
+            return;
+        };
+        let symbols = live_markets(
+            &self.watch_list,
+            &self.rows,
+            &self.config.favorites,
+            self.config.max_live_markets,
+        );
+        self.live_markets = symbols.len();
+        if feed.unbounded_send(symbols).is_err() {
+            self.feed = None;
+        }
+    }
+
+    /// Whether every listed market is followed over the live feed, so polling can slow down.
+    fn all_live(&self) -> bool {
+        self.is_live() && self.live_markets >= self.watch_list.len()
+    }
+
+    /// Notifies about the alerts the latest prices set off.
+    fn check_alerts(&mut self) -> Task<Message> {
+        let fired = alerts::check(
+            &self.config.alerts,
+            &mut self.alert_states,
+            &self.watch_list,
+            self.clock.now(),
+        );
+        let Some(message) = fired
+            .iter()
+            .map(ToString::to_string)
+            .reduce(|all, alert| format!("{all}, {alert}"))
+        else {
+            return Task::none();
+        };
+        if let Err(e) = alerts::notify("krader alert", &message) {
+            log::warn!("Couldn't show a notification: {e}");
+        }
+        self.show_toast(format!("Alert: {message}"))
+    }
+
+    /// Copies the `symbol` row to the clipboard as `format` writes it.
+    fn copy_row(&mut self, symbol: &str, format: fn(&WatchItem) -> String) -> Task<Message> {
+        self.row_menu = None;
+        let Some(item) = self
+            .watch_list
+            .iter()
+            .find(|item| item.symbol.as_deref() == Some(symbol))
+        else {
+            return self.show_toast(format!("{symbol} is no longer listed"));
+        };
+
+        Task::batch([
+            iced::clipboard::write(format(item)),
+            self.show_toast(format!("Copied {symbol}!")),
+        ])
+    }
+
+    /// Shows `message` in the status row for a few seconds.
+    fn show_toast(&mut self, message: String) -> Task<Message> {
+        let id = self.toast.as_ref().map_or(0, |toast| toast.id + 1);
+        self.toast = Some(Toast { id, message });
+
+        Task::perform(tokio::time::sleep(TOAST_DURATION), move |_| {
+            Message::DismissToast(id)
+        })
+    }
+
+    /// Applies `sort` to the columns and rows and saves it for the next launch.
+    ///
+    /// A sort by a column that is no longer shown, like one hidden since the last run, falls back
+    /// to the feed's order.
+    fn sort_changed(&mut self) {
+        if self
+            .sort
+            .is_some_and(|(kind, _)| !self.columns.iter().any(|column| column.kind == kind))
+        {
+            self.sort = None;
+        }
+        if self.config.sort != self.sort {
+            self.config.sort = self.sort;
+            if let Err(e) = self.config.save() {
+                log::error!("{e}");
+            }
+        }
+        for column in &mut self.columns {
+            column.sort = self
+                .sort
+                .filter(|(kind, _)| *kind == column.kind)
+                .map(|(_, direction)| direction);
+        }
+        self.refresh_rows();
+    }
+
+    /// Rebuilds the displayed rows from `watch_list`, the active filters and the sort.
+    fn refresh_rows(&mut self) {
+        self.rows = self
+            .watch_list
+            .iter()
+            .filter(|item| self.filters.matches(item))
+            .cloned()
+            .collect();
+        if let Some(order) = &self.frozen_order {
+            let positions: HashMap<&str, usize> = order
+                .iter()
+                .enumerate()
+                .map(|(position, symbol)| (symbol.as_str(), position))
+                .collect();
+            // Markets listed since the freeze have no position and go last, in feed order.
+            self.rows.sort_by_key(|item| {
+                item.symbol
+                    .as_deref()
+                    .and_then(|symbol| positions.get(symbol))
+                    .map_or(usize::MAX, |position| *position)
+            });
+        } else if let Some((kind, direction)) = self.sort {
+            // A stable sort keeps ties in feed order, so rows don't shuffle between refreshes.
+            self.rows
+                .sort_by(|a, b| sort::compare(kind, direction, a, b));
+        }
+        // Favorites float to the top, keeping the order above among themselves and the rest.
+        let favorites = &self.config.favorites;
+        self.rows.sort_by_key(|item| {
+            !item
+                .symbol
+                .as_ref()
+                .is_some_and(|symbol| favorites.contains(symbol))
+        });
+        for column in &mut self.columns {
+            if matches!(column.kind, ColumnKind::BidSize | ColumnKind::AskSize) {
+                column.distribution = self
+                    .rows
+                    .iter()
+                    .filter_map(|item| column.kind.number(item))
+                    .filter(|value| !value.is_nan())
+                    .collect();
+                column.distribution.sort_by(f64::total_cmp);
+            }
+        }
+        let movers = self
+            .config
+            .highlight_movers
+            .map(|n| summary::movers(&self.rows, n))
+            .unwrap_or_default();
+        for column in &mut self.columns {
+            column.movers.clone_from(&movers);
+            if column.kind == ColumnKind::Sparkline {
+                column.history.clone_from(&self.history);
+            }
+        }
+        self.table_rows = grouping::group(&self.rows, self.config.grouping, &self.collapsed_groups);
+        self.histogram = Histogram::new(self.rows.iter().filter_map(|item| item.change24h));
+        for column in &mut self.columns {
+            column.refresh_texts(&self.rows);
+        }
+        // Favorites, filters and the sort decide which markets the live feed follows.
+        self.follow_markets();
+    }
+
+    /// Re-reads the allowlist file when that filter is on, so edits to the file are picked up.
+    fn reload_allowlist(&mut self) {
+        self.filters.allowlist = None;
+        self.allowlist_error = None;
+
+        if self.config.allowlist_only && !self.config.allowlist_path.is_empty() {
+            match allowlist::load(Path::new(&self.config.allowlist_path)) {
+                Ok(allowlist) => self.filters.allowlist = Some(allowlist),
+                Err(e) => self.allowlist_error = Some(format!("Can't read allowlist: {e}")),
+            }
+        }
+        self.refresh_rows();
+    }
+
+    /// Formats a summary bar total, abbreviated like the volume columns when that is enabled.
+    fn format_total(&self, value: f64) -> String {
+        if self.config.abbreviate_volumes {
+            format::abbreviate(value, self.config.abbrev_decimals)
+        } else {
+            format!("{value:.2}")
+        }
+    }
+
+    fn select(&mut self, symbol: Option<String>) {
+        for column in &mut self.columns {
+            column.selected.clone_from(&symbol);
+        }
+    }
+
+    fn run_row_action(&mut self, action: RowAction, symbol: String) -> Task<Message> {
+        match action {
+            RowAction::Nothing => Task::none(),
+            RowAction::Select => {
+                // Selecting the selected row again clears the selection.
+                let selected = (self.columns.first().and_then(|c| c.selected.as_ref())
+                    != Some(&symbol))
+                .then_some(symbol);
+                self.select(selected);
+                Task::none()
+            }
+            RowAction::OpenDetail => {
+                // Clicking the market whose panel is open closes it again.
+                if self.detail.as_ref() == Some(&symbol) {
+                    self.detail = None;
+                } else {
+                    self.select(Some(symbol.clone()));
+                    self.detail = Some(symbol);
+                }
+                Task::none()
+            }
+            RowAction::CopySymbol => Task::batch([
+                iced::clipboard::write(symbol.clone()),
+                self.show_toast(format!("Copied {symbol}")),
+            ]),
+            RowAction::OpenWeb => {
+                match row_action::open_in_browser(&row_action::web_url(
+                    &symbol,
+                    self.config.data_source,
+                )) {
+                    Ok(()) => Task::none(),
+                    Err(e) => self.show_toast(format!("Couldn't open a browser: {e}")),
+                }
+            }
+        }
+    }
+
+    /// Records the current column order and widths in the config, so they survive restarts.
+    ///
+    /// Columns currently hidden keep their saved entries, after the shown ones.
+    fn store_layout(&mut self) {
+        let mut layout: Vec<ColumnLayout> = self
+            .columns
+            .iter()
+            .map(|column| ColumnLayout {
+                kind: column.kind,
+                width: column.width,
+            })
+            .collect();
+        let hidden: Vec<ColumnLayout> = self
+            .config
+            .columns
+            .iter()
+            .filter(|saved| !layout.iter().any(|shown| shown.kind == saved.kind))
+            .copied()
+            .collect();
+        layout.extend(hidden);
+        self.config.columns = layout;
+    }
+
+    /// Lays the columns out again after the set of columns changed, keeping resized widths.
+    fn rebuild_columns(&mut self) {
+        self.store_layout();
+        let selected = self
+            .columns
+            .first()
+            .and_then(|column| column.selected.clone());
+
+        self.columns =
+            WatchlistColumn::all_from_config(&self.config, &CellOptions::from_config(&self.config));
+        for column in &mut self.columns {
+            column.selected.clone_from(&selected);
+            if column.kind == ColumnKind::Sparkline {
+                column.history.clone_from(&self.history);
+            }
+            column.refresh_texts(&self.rows);
+        }
+    }
+
+    /// Applies `edit` to the transform of the column whose menu is open.
+    fn edit_transform(&mut self, edit: impl FnOnce(&mut Transform)) {
+        let Some(menu) = &self.column_menu else {
+            return;
+        };
+        let mut transform = self.config.transform(menu.kind);
+        edit(&mut transform);
+
+        if transform.is_identity() {
+            self.config.transforms.remove(&menu.kind);
+        } else {
+            self.config.transforms.insert(menu.kind, transform);
+        }
+        self.config_changed();
+    }
+
+    /// Pushes the current config into the columns and writes it to disk.
+    fn config_changed(&mut self) {
+        let options = CellOptions::from_config(&self.config);
+        for column in &mut self.columns {
+            column.options = options.clone();
+            column.transform = self.config.transform(column.kind);
+            column.precision = self.config.precision(column.kind);
+            column.number_style = self.config.number_style(column.kind);
+            column.refresh_texts(&self.rows);
+        }
+
+        if let Err(e) = self.config.save() {
+            log::error!("{e}");
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let summary = summarize(&self.rows, &self.rates, self.summary_currency);
+        let mut summary_bar = row![
+            text(format!(
+                "Quote volume: {} {}",
+                self.format_total(summary.volume_quote),
+                self.summary_currency
+            ))
+            .size(14),
+            text(format!(
+                "Open interest value: {} {}",
+                self.format_total(summary.open_interest_value),
+                self.summary_currency
+            ))
+            .size(14),
+        ]
+        .spacing(20);
+        if summary.excluded > 0 {
+            summary_bar = summary_bar.push(
+                text(format!("{} excluded (no rate)", summary.excluded))
+                    .size(14)
+                    .color(GREY),
+            );
+        }
+        let active_filters = self.filters.active_count();
+        let summary_bar = row![
+            summary_bar,
+            horizontal_space(),
+            button(
+                text(if self.show_histogram {
+                    "Histogram ▾"
+                } else {
+                    "Histogram ▸"
+                })
+                .size(14)
+            )
+            .style(button::text)
+            .on_press(Message::ToggleHistogram),
+            pick_list(
+                &Grouping::ALL[..],
+                Some(self.config.grouping),
+                Message::GroupingChanged
+            )
+            .text_size(14),
+            button(text("Columns").size(14)).on_press(Message::ToggleColumnPicker),
+            button(text("Rules").size(14)).on_press(Message::ToggleRulesEditor),
+            button(text("Alerts").size(14)).on_press(Message::ToggleAlertsEditor),
+            checkbox("Crossed only", self.filters.crossed_only)
+                .on_toggle(Message::CrossedOnlyToggled)
+                .size(14)
+                .text_size(14),
+            checkbox("Hide suspended", self.filters.hide_suspended)
+                .on_toggle(Message::HideSuspendedToggled)
+                .size(14)
+                .text_size(14),
+            text(if self.filters.min_volume > 0.0 {
+                format!("Vol ≥ {}", format::abbreviate(self.filters.min_volume, 0))
+            } else {
+                "Any volume".to_string()
+            })
+            .size(14),
+            // Volumes span many orders of magnitude, so the slider moves through powers of ten.
+            slider(
+                0.0..=MAX_VOLUME_EXPONENT,
+                if self.filters.min_volume > 0.0 {
+                    self.filters.min_volume.log10()
+                } else {
+                    0.0
+                },
+                |exponent| Message::MinVolumeChanged(if exponent > 0.0 {
+                    10f64.powf(exponent)
+                } else {
+                    0.0
+                }),
+            )
+            .step(0.5)
+            .width(100),
+            text(format!("{active_filters} filters active"))
+                .size(14)
+                .color(if active_filters > 0 { YELLOW } else { GREY }),
+            button(text("Clear filters").size(14))
+                .on_press_maybe((active_filters > 0).then_some(Message::ClearAllFilters)),
+            pick_list(
+                &QuoteCurrency::ALL[..],
+                Some(self.summary_currency),
+                Message::SummaryCurrencyChanged,
+            )
+            .text_size(14),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let table = responsive(|size| {
+            let pinned = self.config.pin_first_column && self.columns.len() > 1;
+            let (leading, rest) = self.columns.split_at(usize::from(pinned));
+
+            let mut table = table(
+                self.header.clone(),
+                self.body.clone(),
+                rest,
+                &self.table_rows,
+                Message::SyncHeader,
+            );
+            if self.config.resizable_columns {
+                table = if pinned {
+                    table.on_column_resize(
+                        |index, offset| Message::Resizing(index + 1, offset),
+                        Message::Resized,
+                    )
+                } else {
+                    table.on_column_resize(Message::Resizing, Message::Resized)
+                };
+            }
+            if self.config.show_footer {
+                table = table.footer(self.footer.clone());
+            }
+
+            if !pinned {
+                if self.config.fill_width {
+                    table = table.min_width(size.width);
+                }
+                return table.into();
+            }
+
+            let mut pinned_table = iced_table::table(
+                self.pinned_header.clone(),
+                self.pinned_body.clone(),
+                leading,
+                &self.table_rows,
+                Message::SyncPinned,
+            )
+            // The main table's scrollbar is the one to drag.
+            .scrollbar(scrollable::Scrollbar::new().width(0).scroller_width(0));
+            if self.config.resizable_columns {
+                pinned_table = pinned_table.on_column_resize(Message::Resizing, Message::Resized);
+            }
+            if self.config.show_footer {
+                pinned_table = pinned_table.footer(self.pinned_footer.clone());
+            }
+            let pinned_width = leading[0].width + leading[0].resize_offset.unwrap_or_default();
+            if self.config.fill_width {
+                table = table.min_width((size.width - pinned_width).max(0.0));
+            }
+
+            row![
+                container(pinned_table).width(pinned_width),
+                container(table).width(Length::Fill),
+            ]
+            .into()
+        });
+        let table = container(table).id(self.table_container.clone());
+        let time_status = iced::widget::Row::new()
+            .height(32)
+            .padding(5)
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            // The theme's own success color stays readable on light and dark backgrounds alike.
+            .push(text("Last update: ").size(14).style(text::success))
+            .push(
+                text(self.last_update.map_or("Never".to_string(), |at| {
+                    format!(
+                        "{} ({})",
+                        at.format("%Y-%m-%d %H:%M:%S"),
+                        clock::relative_time(at, self.clock.as_ref())
+                    )
+                }))
+                .size(14)
+                .style(text::success),
+            )
+            .push_maybe(
+                self.is_paused
+                    .then(|| text("PAUSED").size(14).color(YELLOW)),
+            )
+            .push_maybe(
+                self.last_update
+                    .filter(|_| !self.is_paused)
+                    .filter(|at| {
+                        let threshold = self.config.refresh_interval() * STALE_AFTER_POLLS;
+                        clock::is_stale(*at, threshold, self.clock.as_ref())
+                    })
+                    .map(|at| {
+                        let mut badge = format!(
+                            "STALE — last updated {}",
+                            clock::relative_time(at, self.clock.as_ref())
+                        );
+                        match self.consecutive_failures {
+                            0 => {}
+                            1 => badge.push_str(", 1 failed fetch since"),
+                            failures => {
+                                badge.push_str(&format!(", {failures} failed fetches since"));
+                            }
+                        }
+                        text(badge).size(14).color(YELLOW)
+                    }),
+            )
+            .push_maybe(self.is_live().then(|| {
+                let mut badge = format!("LIVE ({} subscribed)", self.live_markets);
+                if self.feed_reconnects > 0 {
+                    badge.push_str(&format!(", reconnected {}×", self.feed_reconnects));
+                }
+                text(badge).size(14).color(GREEN)
+            }))
+            .push_maybe(
+                self.last_update
+                    .filter(|_| !self.mock_data && !self.is_paused && !self.all_live())
+                    .map(|at| {
+                        let left = clock::countdown(
+                            at + self.config.refresh_interval(),
+                            self.clock.as_ref(),
+                        );
+                        text(format!("next in {}s", left.as_secs()))
+                            .size(14)
+                            .color(GREY)
+                    }),
+            )
+            .push_maybe(self.mock_data.then(|| {
+                row![
+                    text("OFFLINE (mock data)").size(14).color(YELLOW),
+                    button(text("Go online").size(14))
+                        .padding([2, 8])
+                        .on_press(Message::UseMockData(false)),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center)
+            }))
+            .push_maybe(self.sort.map(|(kind, direction)| {
+                text(format!("Sorted by {} {}", kind.label(), direction.glyph())).size(14)
+            }))
+            .push(
+                button(text(if self.is_paused { "Resume" } else { "Pause" }).size(14))
+                    .padding([2, 8])
+                    .on_press(Message::TogglePause),
+            )
+            .push(if self.frozen_order.is_some() {
+                button(text("Unfreeze order").size(14))
+                    .padding([2, 8])
+                    .on_press(Message::UnfreezeOrder)
+            } else {
+                button(text("Freeze order").size(14))
+                    .padding([2, 8])
+                    .on_press(Message::FreezeOrder)
+            })
+            .push_maybe(
+                self.toast
+                    .as_ref()
+                    .map(|toast| text(&toast.message).size(14)),
+            )
+            .push(horizontal_space())
+            .push(
+                pick_list(
+                    &REFRESH_INTERVALS[..],
+                    Some(RefreshInterval(self.config.refresh_interval())),
+                    |RefreshInterval(interval)| Message::IntervalChanged(interval),
+                )
+                .text_size(14),
+            )
+            .push(
+                button(
+                    text(if self.fetches.in_flight {
+                        "Refreshing…"
+                    } else {
+                        "Refresh"
+                    })
+                    .size(14),
+                )
+                .padding([2, 8])
+                .on_press_maybe((!self.fetches.in_flight).then_some(Message::FetchData)),
+            )
+            .push(
+                button(text("Snapshot").size(14))
+                    .padding([2, 8])
+                    .on_press(Message::CopyTableImage),
+            )
+            .push(
+                button(text("Settings").size(14))
+                    .padding([2, 8])
+                    .on_press(Message::ToggleSettings),
+            );
+
+        let mut content = column![].spacing(6);
+        // Before the first data arrives, the retrying and offline screens show the error instead.
+        if let Some(error) = self
+            .last_error
+            .as_ref()
+            .filter(|_| self.load_state == LoadState::Loaded)
+        {
+            content = content.push(error_banner(error, self.fetches.in_flight));
+        }
+        let mut content = content.push(summary_bar);
+        if let Some(query) = &self.palette {
+            content = content.push(self.palette_view(query));
+        }
+        if self.show_help {
+            content = content.push(help_view());
+        }
+        if self.config.show_mover_banner && !self.mover_dismissed {
+            content = content.push_maybe(self.mover_banner());
+        }
+        if self.show_histogram {
+            content = content.push(canvas(&self.histogram).width(Length::Fill).height(80));
+        }
+        if self.show_column_picker {
+            content = content.push(self.column_picker_view());
+        }
+        if let Some(menu) = &self.column_menu {
+            content = content.push(self.column_menu_view(menu));
+        }
+        if let Some(draft) = &self.rule_draft {
+            content = content.push(self.rules_view(draft));
+        }
+        if let Some(draft) = &self.alert_draft {
+            content = content.push(self.alerts_view(draft));
+        }
+        if let Some(symbol) = &self.row_menu {
+            content = content.push(
+                row![
+                    text(symbol).size(14),
+                    button(text("Copy row").size(14)).on_press(Message::CopyRow(symbol.clone())),
+                    button(text("Copy JSON").size(14))
+                        .on_press(Message::CopyRowJson(symbol.clone())),
+                    button(text("Report row").size(14))
+                        .on_press(Message::ReportRow(symbol.clone())),
+                    horizontal_space(),
+                    button(text("Close").size(14)).on_press(Message::CloseRowMenu),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+        content = content.push(
+            text_input("Search symbol or pair", &self.search_input)
+                .id(self.search_box.clone())
+                .on_input(Message::SearchChanged)
+                .size(14)
+                .width(260),
+        );
+        let mut content = match &self.load_state {
+            LoadState::Failed(e) => content.push(offline_view(e, self.fetches.in_flight)),
+            LoadState::Retrying { attempt, error } => content.push(retrying_view(
+                *attempt,
+                self.config.first_load_attempts,
+                error,
+                self.fetches.in_flight,
+            )),
+            LoadState::Loading => content.push(loading_view(self.spinner_frame)),
+            LoadState::Loaded => match self.detail.as_ref().and_then(|symbol| {
+                self.watch_list
+                    .iter()
+                    .find(|item| item.symbol.as_ref() == Some(symbol))
+            }) {
+                Some(item) => content.push(row![table, detail_view(item)].spacing(8)),
+                None => content.push(table),
+            },
+        };
+        if self.show_settings {
+            content = content.push(self.settings_view());
+        }
+        let content = content.push(time_status);
+
+        let outer = container(container(content).width(Length::Fill).height(Length::Fill))
+            .padding(self.config.outer_padding);
+        if self.config.center_content {
+            outer.center_x(Length::Fill).center_y(Length::Fill).into()
+        } else {
+            outer.width(Length::Fill).height(Length::Fill).into()
+        }
+    }
+
+    fn palette_view<'a>(&'a self, query: &'a str) -> Element<'a, Message> {
+        let matches = palette::search(palette::commands(self), query)
+            .into_iter()
+            .map(|command| {
+                button(text(command.title).size(14))
+                    .style(button::text)
+                    .width(Length::Fill)
+                    .on_press(Message::RunCommand(Box::new(command.message)))
+                    .into()
+            });
+
+        container(
+            column![
+                text_input("Type a command…", query)
+                    .id(self.palette_input.clone())
+                    .on_input(Message::PaletteQueryChanged)
+                    .on_submit(Message::PaletteSubmitted)
+                    .size(14),
+                iced::widget::Column::with_children(matches),
+            ]
+            .spacing(6),
+        )
+        .padding(8)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    fn mover_banner(&self) -> Option<Element<'_, Message>> {
+        let (item, change) = summary::biggest_mover(&self.rows)?;
+        let symbol = item.symbol.clone()?;
+
+        Some(
+            row![
+                button(
+                    text(format!("Biggest mover: {symbol} {change:+.2}% since open"))
+                        .size(16)
+                        .color(if change >= 0.0 { GREEN } else { RED }),
+                )
+                .style(button::text)
+                .padding(0)
+                .on_press(Message::SelectRow(symbol)),
+                horizontal_space(),
+                button(text("✕").size(14))
+                    .style(button::text)
+                    .padding([0, 4])
+                    .on_press(Message::DismissMoverBanner),
+            ]
+            .align_y(iced::Alignment::Center)
+            .into(),
+        )
+    }
+
+    fn settings_view(&self) -> Element<'_, Message> {
+        let theme_choice = self.config.theme_choice();
+
+        row![
+            row![
+                text("Markets").size(14),
+                pick_list(
+                    &DataSource::ALL[..],
+                    Some(self.config.data_source),
+                    Message::DataSourceChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                text("Theme").size(14),
+                pick_list(
+                    &ThemeChoice::ALL[..],
+                    Some(theme_choice),
+                    Message::ThemeChoiceChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            checkbox("Highlight crossed", self.config.highlight_crossed)
+                .on_toggle(Message::HighlightCrossedToggled)
+                .size(14)
+                .text_size(14),
+            row![
+                text("Decimals").size(14),
+                pick_list(
+                    &Precision::ALL[..],
+                    Some(self.config.decimals),
+                    Message::DecimalsChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                checkbox("Whole volumes", self.config.whole_volumes)
+                    .on_toggle(Message::WholeVolumesToggled)
+                    .size(14)
+                    .text_size(14),
+                pick_list(
+                    &Separator::ALL[..],
+                    Some(self.config.thousands_separator),
+                    Message::SeparatorChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                checkbox("Abbreviate volumes", self.config.abbreviate_volumes)
+                    .on_toggle(Message::AbbreviateVolumesToggled)
+                    .size(14)
+                    .text_size(14),
+                pick_list(
+                    &ABBREV_DECIMALS[..],
+                    Some(self.config.abbrev_decimals),
+                    Message::AbbrevDecimalsChanged
+                )
+                .text_size(14),
+                text("decimals").size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                checkbox("Highlight top", self.config.highlight_movers.is_some())
+                    .on_toggle(|enabled| Message::HighlightMoversChanged(
+                        enabled.then_some(DEFAULT_MOVER_COUNT)
+                    ))
+                    .size(14)
+                    .text_size(14),
+                pick_list(
+                    &MOVER_COUNTS[..],
+                    Some(self.config.highlight_movers.unwrap_or(DEFAULT_MOVER_COUNT)),
+                    |count| Message::HighlightMoversChanged(Some(count))
+                )
+                .text_size(14),
+                text("movers").size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                text("Wide spread above").size(14),
+                pick_list(
+                    &SPREAD_THRESHOLDS[..],
+                    Some(SpreadThreshold(self.config.wide_spread_threshold)),
+                    |SpreadThreshold(threshold)| Message::WideSpreadThresholdChanged(threshold)
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            checkbox("Monospace numbers", self.config.monospace_numbers)
+                .on_toggle(Message::MonospaceNumbersToggled)
+                .size(14)
+                .text_size(14),
+            row![
+                text("Padding").size(14),
+                pick_list(
+                    &OUTER_PADDINGS[..],
+                    Some(self.config.outer_padding),
+                    Message::OuterPaddingChanged
+                )
+                .text_size(14),
+                checkbox("Centered", self.config.center_content)
+                    .on_toggle(Message::CenterContentToggled)
+                    .size(14)
+                    .text_size(14),
+                checkbox("Pin first column", self.config.pin_first_column)
+                    .on_toggle(Message::PinFirstColumnToggled)
+                    .size(14)
+                    .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                text("24h change").size(14),
+                pick_list(
+                    &ChangeDisplay::ALL[..],
+                    Some(self.config.change_display),
+                    Message::ChangeDisplayChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            row![
+                checkbox("Resizable columns", self.config.resizable_columns)
+                    .on_toggle(Message::ResizableColumnsToggled)
+                    .size(14)
+                    .text_size(14),
+                checkbox("Totals row", self.config.show_footer)
+                    .on_toggle(Message::FooterToggled)
+                    .size(14)
+                    .text_size(14),
+                checkbox("Fill window width", self.config.fill_width)
+                    .on_toggle(Message::FillWidthToggled)
+                    .size(14)
+                    .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            checkbox("Color sizes", self.config.color_sizes)
+                .on_toggle(Message::ColorSizesToggled)
+                .size(14)
+                .text_size(14),
+            checkbox("Merged quote column", self.config.merge_quote)
+                .on_toggle(Message::MergeQuoteToggled)
+                .size(14)
+                .text_size(14),
+            checkbox("Biggest mover banner", self.config.show_mover_banner)
+                .on_toggle(Message::MoverBannerToggled)
+                .size(14)
+                .text_size(14),
+            row![
+                checkbox("Live updates", self.config.live_feed)
+                    .on_toggle(Message::LiveFeedToggled)
+                    .size(14)
+                    .text_size(14),
+                text("for up to").size(14),
+                pick_list(
+                    &LIVE_MARKET_LIMITS[..],
+                    Some(self.config.max_live_markets),
+                    Message::MaxLiveMarketsChanged
+                )
+                .text_size(14),
+                text("markets").size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            checkbox("Value tooltips", self.config.cell_tooltips)
+                .on_toggle(Message::CellTooltipsToggled)
+                .size(14)
+                .text_size(14),
+            checkbox("Striped rows", self.config.zebra_stripes)
+                .on_toggle(Message::ZebraStripesToggled)
+                .size(14)
+                .text_size(14),
+            checkbox("Compact rows", self.config.density == Density::Compact)
+                .on_toggle(Message::CompactRowsToggled)
+                .size(14)
+                .text_size(14),
+            row![
+                text("Click").size(14),
+                pick_list(
+                    &RowAction::ALL[..],
+                    Some(self.config.row_click_action),
+                    Message::RowClickActionChanged
+                )
+                .text_size(14),
+                text("Double-click").size(14),
+                pick_list(
+                    &RowAction::ALL[..],
+                    Some(self.config.row_double_click_action),
+                    Message::RowDoubleClickActionChanged
+                )
+                .text_size(14),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center),
+            self.allowlist_view(),
+        ]
+        .spacing(20)
+        .padding(5)
+        .align_y(iced::Alignment::Center)
+        .wrap()
+        .into()
+    }
+
+    fn allowlist_view(&self) -> Element<'_, Message> {
+        let status = if let Some(error) = &self.allowlist_error {
+            Some(text(error).size(12).color(RED))
+        } else {
+            self.filters
+                .allowlist
+                .as_ref()
+                .filter(|_| self.load_state == LoadState::Loaded)
+                .map(|allowlist| allowlist::unmatched(allowlist, &self.watch_list))
+                .filter(|unmatched| !unmatched.is_empty())
+                .map(|unmatched| {
+                    text(format!("No market matches: {}", unmatched.join(", ")))
+                        .size(12)
+                        .color(YELLOW)
+                })
+        };
+
+        row![
+            checkbox("Only allowlisted", self.config.allowlist_only)
+                .on_toggle(Message::AllowlistOnlyToggled)
+                .size(14)
+                .text_size(14),
+            text_input("Allowlist file path", &self.config.allowlist_path)
+                .on_input(Message::AllowlistPathChanged)
+                .on_submit(Message::ReloadAllowlist)
+                .size(14)
+                .width(260),
+        ]
+        .push_maybe(status)
+        .spacing(6)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn rules_view<'a>(&'a self, draft: &'a RuleDraft) -> Element<'a, Message> {
+        let numeric: Vec<ColumnKind> = ColumnKind::ALL
+            .into_iter()
+            .filter(ColumnKind::is_numeric)
+            .collect();
+
+        let rules = self.config.rules.iter().enumerate().map(|(index, rule)| {
+            row![
+                text(format!("{}. {rule}", index + 1)).size(14),
+                button(text("Remove").size(12))
+                    .padding([0, 6])
+                    .on_press(Message::RemoveRule(index)),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+
+        let form = row![
+            text("Color").size(14),
+            pick_list(&ColumnKind::ALL[..], Some(draft.target), move |target| {
+                Message::RuleDraftChanged(RuleDraft {
+                    target,
+                    ..draft.clone()
+                })
+            })
+            .text_size(14),
+            pick_list(&RuleColor::ALL[..], Some(draft.color), move |color| {
+                Message::RuleDraftChanged(RuleDraft {
+                    color,
+                    ..draft.clone()
+                })
+            })
+            .text_size(14),
+            checkbox("background", draft.background)
+                .on_toggle(move |background| {
+                    Message::RuleDraftChanged(RuleDraft {
+                        background,
+                        ..draft.clone()
+                    })
+                })
+                .size(14)
+                .text_size(14),
+            text("when").size(14),
+            pick_list(numeric, Some(draft.field), move |field| {
+                Message::RuleDraftChanged(RuleDraft {
+                    field,
+                    ..draft.clone()
+                })
+            })
+            .text_size(14),
+            pick_list(
+                &Comparator::ALL[..],
+                Some(draft.comparator),
+                move |comparator| {
+                    Message::RuleDraftChanged(RuleDraft {
+                        comparator,
+                        ..draft.clone()
+                    })
+                }
+            )
+            .text_size(14),
+            text_input("value", &draft.value)
+                .on_input(move |value| {
+                    Message::RuleDraftChanged(RuleDraft {
+                        value,
+                        ..draft.clone()
+                    })
+                })
+                .size(14)
+                .width(80),
+            button(text("Add").size(14)).on_press_maybe(draft.rule().map(|_| Message::AddRule)),
+            horizontal_space(),
+            button(text("Close").size(14)).on_press(Message::ToggleRulesEditor),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        column(rules).push(form).spacing(4).into()
+    }
+
+    fn alerts_view<'a>(&'a self, draft: &'a AlertDraft) -> Element<'a, Message> {
+        let numeric: Vec<ColumnKind> = ColumnKind::ALL
+            .into_iter()
+            .filter(ColumnKind::is_numeric)
+            .collect();
+
+        let alerts = self.config.alerts.iter().enumerate().map(|(index, alert)| {
+            row![
+                text(format!("{}. {alert}", index + 1)).size(14),
+                button(text("Remove").size(12))
+                    .padding([0, 6])
+                    .on_press(Message::RemoveAlert(index)),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+
+        let form = row![
+            text("Notify when").size(14),
+            text_input("symbol", &draft.symbol)
+                .on_input(move |symbol| {
+                    Message::AlertDraftChanged(AlertDraft {
+                        symbol,
+                        ..draft.clone()
+                    })
+                })
+                .size(14)
+                .width(120),
+            pick_list(numeric, Some(draft.field), move |field| {
+                Message::AlertDraftChanged(AlertDraft {
+                    field,
+                    ..draft.clone()
+                })
+            })
+            .text_size(14),
+            pick_list(
+                &Comparator::ALL[..],
+                Some(draft.comparator),
+                move |comparator| {
+                    Message::AlertDraftChanged(AlertDraft {
+                        comparator,
+                        ..draft.clone()
+                    })
+                }
+            )
+            .text_size(14),
+            text_input("value", &draft.value)
+                .on_input(move |value| {
+                    Message::AlertDraftChanged(AlertDraft {
+                        value,
+                        ..draft.clone()
+                    })
+                })
+                .size(14)
+                .width(80),
+            button(text("Add").size(14)).on_press_maybe(draft.alert().map(|_| Message::AddAlert)),
+            horizontal_space(),
+            button(text("Close").size(14)).on_press(Message::ToggleAlertsEditor),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        column(alerts).push(form).spacing(4).into()
+    }
+
+    fn column_picker_view(&self) -> Element<'_, Message> {
+        let toggles = ColumnKind::layout(self.config.merge_quote)
+            .into_iter()
+            .map(|kind| {
+                checkbox(kind.label(), !self.config.hidden_columns.contains(&kind))
+                    .on_toggle(move |_| Message::ToggleColumn(kind))
+                    .size(14)
+                    .text_size(14)
+                    .into()
+            });
+
+        row![
+            iced::widget::Row::with_children(toggles).spacing(16).wrap(),
+            button(text("Close").size(14)).on_press(Message::ToggleColumnPicker),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    fn column_menu_view<'a>(&'a self, menu: &'a ColumnMenu) -> Element<'a, Message> {
+        let transform = self.config.transform(menu.kind);
+
+        row![
+            text(format!("{} transform:", menu.kind.label())).size(14),
+            checkbox("abs", transform.abs)
+                .on_toggle(Message::TransformAbsToggled)
+                .size(14)
+                .text_size(14),
+            text("×").size(14),
+            text_input("1", &menu.multiply)
+                .on_input(Message::TransformMultiplyChanged)
+                .size(14)
+                .width(80),
+            text("+").size(14),
+            text_input("0", &menu.add)
+                .on_input(Message::TransformAddChanged)
+                .size(14)
+                .width(80),
+            text_input("suffix", &transform.suffix)
+                .on_input(Message::TransformSuffixChanged)
+                .size(14)
+                .width(80),
+            button(text("Reset").size(14)).on_press(Message::ResetTransform),
+        ]
+        .push_maybe(menu.kind.is_price().then(|| {
+            pick_list(
+                &Precision::ALL[..],
+                Some(self.config.precision(menu.kind)),
+                move |precision| Message::PrecisionChanged(menu.kind, precision),
+            )
+            .text_size(14)
+        }))
+        .push_maybe(menu.kind.is_quantity().then(|| {
+            pick_list(
+                &NumberStyle::ALL[..],
+                Some(self.config.number_style(menu.kind)),
+                move |style| Message::NumberStyleChanged(menu.kind, style),
+            )
+            .text_size(14)
+        }))
+        .push(
+            checkbox(
+                "Flash on change",
+                self.config.flash_fields.contains(&menu.kind),
+            )
+            .on_toggle(|enabled| Message::FlashFieldToggled(menu.kind, enabled))
+            .size(14)
+            .text_size(14),
+        )
+        .push(horizontal_space())
+        .push(button(text("Close").size(14)).on_press(Message::ToggleColumnMenu(menu.kind)))
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // The first load retries on its own schedule, so polling waits until it settles.
+        let prices = match self.load_state {
+            LoadState::Loading => every(SPINNER_INTERVAL).map(|_| Message::SpinnerTick),
+            LoadState::Retrying { .. } => Subscription::none(),
+            LoadState::Failed(_) | LoadState::Loaded if self.is_paused => Subscription::none(),
+            LoadState::Failed(_) | LoadState::Loaded => {
+                // A hand-edited config could ask for zero, which would poll in a busy loop.
+                let mut interval = self
+                    .config
+                    .refresh_interval()
+                    .max(Duration::from_secs(1))
+                    .max(self.rate_limit.unwrap_or_default());
+                // Markets beyond the live feed's limit still need polling at the usual rate.
+                if self.all_live() {
+                    interval = interval.max(LIVE_SNAPSHOT_INTERVAL);
+                }
+                every(interval).map(|_| Message::FetchData)
+            }
+        };
+        let feed = if self.live_feed_wanted() {
+            Subscription::run_with_id(self.feed_url.clone(), live::connect(self.feed_url.clone()))
+        } else {
+            Subscription::none()
+        };
+        let live_updates = if self.live_changed {
+            every(LIVE_REFRESH_INTERVAL).map(|_| Message::ApplyLiveUpdates)
+        } else {
+            Subscription::none()
+        };
+        let window_events = iced::event::listen_with(|event, _status, _id| match event {
+            iced::Event::Window(window::Event::Focused) => Some(Message::WindowFocused),
+            iced::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+            iced::Event::Window(window::Event::Moved(position)) => {
+                Some(Message::WindowMoved(position))
+            }
+            _ => None,
+        });
+
+        // Only sees keys no widget captured, so nothing fires while typing in a text input.
+        let keys = iced::keyboard::on_key_press(|key, modifiers| {
+            if modifiers.command() {
+                return match key.as_ref() {
+                    iced::keyboard::Key::Character("p") => Some(Message::OpenPalette),
+                    iced::keyboard::Key::Character("r") => Some(Message::FetchData),
+                    iced::keyboard::Key::Character("f") => Some(Message::FocusSearch),
+                    iced::keyboard::Key::Character("c") => Some(Message::CopySelectedRow),
+                    _ => None,
+                };
+            }
+            if modifiers.alt() {
+                return None;
+            }
+            match key.as_ref() {
+                iced::keyboard::Key::Character("s") => Some(Message::CycleSortColumn),
+                iced::keyboard::Key::Character("d") => Some(Message::ToggleSortDirection),
+                iced::keyboard::Key::Character("?") => Some(Message::ToggleHelp),
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::Space) => {
+                    Some(Message::TogglePause)
+                }
+                _ => None,
+            }
+        });
+
+        // Text inputs capture Escape, so listen regardless of event status.
+        let escape = if self.palette.is_some() {
+            iced::event::listen_with(|event, _status, _id| {
+                is_escape(&event).then_some(Message::ClosePalette)
+            })
+        } else {
+            iced::event::listen_with(|event, _status, _id| {
+                is_escape(&event).then_some(Message::Escape)
+            })
+        };
+
+        // Keeps the relative time and countdown in the status row current between fetches.
+        let tick = every(Duration::from_secs(1)).map(|_| Message::Tick);
+
+        // Redraw every frame only while a flash is fading.
+        let flashes = if self.columns.iter().any(|c| !c.flashing.is_empty()) {
+            window::frames().map(Message::FlashFrame)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(vec![
+            prices,
+            feed,
+            live_updates,
+            window_events,
+            keys,
+            escape,
+            tick,
+            flashes,
+        ])
+    }
+
+    fn theme(&self) -> Theme {
+        match self.config.theme_choice() {
+            ThemeChoice::System => self.system_theme.theme(),
+            ThemeChoice::Explicit(mode) => mode.theme(),
+        }
+    }
+}
+
+/// A "Retry now" button, disabled while a fetch is in flight.
+fn retry_button<'a>(label: &'a str, in_flight: bool) -> button::Button<'a, Message> {
+    button(text(label).size(14)).on_press_maybe((!in_flight).then_some(Message::FetchData))
+}
+
+fn error_banner(error: &str, in_flight: bool) -> Element<'_, Message> {
+    container(
+        row![
+            text(error).size(14).color(Color::WHITE),
+            horizontal_space(),
+            retry_button("Retry now", in_flight).padding([2, 8]),
+            button(text("✕").size(14).color(Color::WHITE))
+                .style(button::text)
+                .padding([0, 4])
+                .on_press(Message::DismissError),
+        ]
+        .align_y(iced::Alignment::Center),
+    )
+    .padding([4, 8])
+    .style(|_| container::Style {
+        background: Some(RED.into()),
+        border: iced::border::rounded(4),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Every field of one market, for the side panel beside the table.
+fn detail_view(item: &WatchItem) -> Element<'_, Message> {
+    let fields = ColumnKind::ALL
+        .into_iter()
+        .filter(|kind| {
+            !matches!(
+                kind,
+                ColumnKind::Quote | ColumnKind::Favorite | ColumnKind::Sparkline
+            )
+        })
+        .map(|kind| {
+            let value = match kind.number(item) {
+                Some(value) if kind.is_fraction() => format::percent(value),
+                Some(value) => value.to_string(),
+                None => kind.text(item).unwrap_or("N/A").to_string(),
+            };
+            row![
+                text(kind.name()).size(14).width(Length::Fill),
+                text(value).size(14),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+    container(
+        column![
+            row![
+                text(item.symbol.as_deref().unwrap_or("N/A")).size(16),
+                horizontal_space(),
+                button(text("✕").size(14))
+                    .style(button::text)
+                    .padding([0, 4])
+                    .on_press(Message::CloseDetail),
+            ]
+            .align_y(iced::Alignment::Center),
+            scrollable(iced::widget::Column::with_children(fields).spacing(4)),
+        ]
+        .spacing(8),
+    )
+    .width(DETAIL_WIDTH)
+    .height(Length::Fill)
+    .padding(8)
+    .style(container::rounded_box)
+    .into()
+}
+
+fn is_escape(event: &iced::Event) -> bool {
+    matches!(
+        event,
+        iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })
+    )
+}
 
-        let content = column![table, time_status].spacing(6);
+/// Keyboard shortcuts and what they do, as listed in the help overlay.
+const SHORTCUTS: [(&str, &str); 9] = [
+    ("Ctrl+R", "Refresh now"),
+    ("Space", "Pause or resume live updates"),
+    ("Ctrl+F", "Focus the search box"),
+    ("Ctrl+C", "Copy the selected row"),
+    ("Esc", "Clear the search, or close this help"),
+    ("Ctrl+P", "Open the command palette"),
+    ("S", "Sort by the next column"),
+    ("D", "Reverse the sort direction"),
+    ("?", "Show or hide this help"),
+];
 
-        container(container(content).width(Length::Fill).height(Length::Fill))
-            .padding(20)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
+fn help_view() -> Element<'static, Message> {
+    let shortcuts = SHORTCUTS.iter().map(|(keys, action)| {
+        row![text(*keys).size(14).width(80), text(*action).size(14)]
+            .spacing(10)
             .into()
-    }
+    });
 
-    fn subscription(&self) -> Subscription<Message> {
-        let prices = every(Duration::from_secs(5)).map(|_| Message::FetchData);
+    container(
+        column![
+            row![
+                text("Keyboard shortcuts").size(16),
+                horizontal_space(),
+                button(text("Close").size(14)).on_press(Message::ToggleHelp),
+            ]
+            .align_y(iced::Alignment::Center),
+            iced::widget::Column::with_children(shortcuts).spacing(4),
+            text("On macOS, Cmd stands in for Ctrl.").size(12),
+        ]
+        .spacing(8),
+    )
+    .padding(8)
+    .style(container::rounded_box)
+    .into()
+}
 
-        Subscription::batch(vec![prices])
-    }
+/// Shown in place of the table until the first fetch answers.
+fn loading_view(frame: usize) -> Element<'static, Message> {
+    let content = column![
+        text(SPINNER[frame % SPINNER.len()]).size(32),
+        text("Loading markets…").size(24),
+    ]
+    .spacing(12)
+    .align_x(iced::Alignment::Center);
 
-    fn theme(&self) -> Theme {
-        Theme::Dark
-    }
+    container(content)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
+
+fn retrying_view(
+    attempt: u32,
+    attempts: u32,
+    error: &str,
+    in_flight: bool,
+) -> Element<'_, Message> {
+    let content = column![
+        text(format!("Retrying ({attempt}/{attempts})…")).size(24),
+        text(error).size(14).color(GREY),
+        retry_button("Retry now", in_flight),
+    ]
+    .spacing(12)
+    .align_x(iced::Alignment::Center);
+
+    container(content)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
+
+/// Shown instead of the table when the very first fetch fails.
+fn offline_view(error: &str, in_flight: bool) -> Element<'_, Message> {
+    let content = column![
+        text("Can't reach Kraken").size(24),
+        text(error).size(14).color(GREY),
+        row![
+            retry_button("Retry", in_flight),
+            button(text("Work offline with mock data")).on_press(Message::UseMockData(true)),
+        ]
+        .spacing(10),
+    ]
+    .spacing(12)
+    .align_x(iced::Alignment::Center);
+
+    container(content)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
+
+async fn fetch_data(
+    client: reqwest::Client,
+    url: String,
+    source: DataSource,
+) -> Result<Vec<WatchItem>, FetchError> {
+    data::fetch(&client, &url, source).await
+}
+
+/// Merges a fetched list into `current` by symbol: known rows are updated in place, markets no
+/// longer listed are dropped and new ones are appended in the order they were fetched.
+///
+/// Rows without a symbol can't be matched, so they are always replaced.
+/// The symbols to follow over the live feed, at most `limit`: favorites, then `rows` in display
+/// order, then the markets the filters hide.
+fn live_markets(
+    watch_list: &[WatchItem],
+    rows: &[WatchItem],
+    favorites: &HashSet<String>,
+    limit: usize,
+) -> Vec<String> {
+    let listed = watch_list.iter().filter_map(|item| item.symbol.as_ref());
+    let favorites = listed.clone().filter(|symbol| favorites.contains(*symbol));
+    let displayed = rows.iter().filter_map(|item| item.symbol.as_ref());
+    let mut seen = HashSet::new();
+    favorites
+        .chain(displayed)
+        .chain(listed)
+        .filter(|symbol| seen.insert(*symbol))
+        .take(limit)
+        .cloned()
+        .collect()
 }
 
-async fn fetch_data() -> Result<Vec<WatchItem>, FetchError> {
-    let url = "https://futures.kraken.com/derivatives/api/v3/tickers".to_string();
-    let resp: TickersResponse = reqwest::get(url).await?.json().await?;
+fn merge_tickers(current: &mut Vec<WatchItem>, fetched: Vec<WatchItem>) {
+    let index: HashMap<&str, usize> = current
+        .iter()
+        .enumerate()
+        .rev()
+        .filter_map(|(i, item)| Some((item.symbol.as_deref()?, i)))
+        .collect();
+
+    let mut updated: Vec<Option<WatchItem>> = vec![None; current.len()];
+    let mut added = Vec::new();
+    for item in fetched {
+        match item.symbol.as_deref().and_then(|symbol| index.get(symbol)) {
+            Some(&i) if updated[i].is_none() => updated[i] = Some(item),
+            _ => added.push(item),
+        }
+    }
 
-    Ok(resp.tickers)
+    *current = updated.into_iter().flatten().chain(added).collect();
 }
 
 pub(crate) struct WatchlistColumn {
     kind: ColumnKind,
     width: f32,
     resize_offset: Option<f32>,
+    options: CellOptions,
+    transform: Transform,
+    /// Symbols whose value in this column just changed, with how it changed.
+    flashing: HashMap<String, Flash>,
+    /// The latest animation frame, which fading flashes are measured against.
+    frame: Instant,
+    /// Whether this is the first column, which also names the group on group header lines.
+    leading: bool,
+    /// Each market's cell text by symbol, kept in step with the rows and settings.
+    texts: HashMap<String, String>,
+    /// The symbol of the selected row.
+    selected: Option<String>,
+    /// This column's values across the displayed rows, sorted, for coloring sizes by rank.
+    distribution: Vec<f64>,
+    /// The displayed markets ranked among the top movers, when that highlight is on.
+    movers: HashMap<String, RankTag>,
+    /// Recent mark prices by symbol, kept only by the sparkline column.
+    history: sparkline::History,
+    /// The direction rows are sorted in, if they are sorted by this column.
+    sort: Option<SortDirection>,
+    precision: Precision,
+    number_style: NumberStyle,
+    /// The time relative ages in this column are measured from, refreshed every tick.
+    now: DateTime<Utc>,
+}
+
+/// The subset of the config that affects how cells are rendered.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CellOptions {
+    monospace_numbers: bool,
+    highlight_crossed: bool,
+    /// Spreads above this fraction of the mark price are shown in red.
+    wide_spread_threshold: f64,
+    /// The thousands separator and decimal mark of grouped numbers.
+    separator: (char, char),
+    abbrev_decimals: usize,
+    change_display: ChangeDisplay,
+    color_sizes: bool,
+    tooltips: bool,
+    zebra_stripes: bool,
+    density: Density,
+    favorites: HashSet<String>,
+    rules: Vec<Rule>,
+}
+
+impl CellOptions {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            monospace_numbers: config.monospace_numbers,
+            highlight_crossed: config.highlight_crossed,
+            wide_spread_threshold: config.wide_spread_threshold,
+            separator: config.thousands_separator.marks(),
+            abbrev_decimals: config.abbrev_decimals,
+            change_display: config.change_display,
+            color_sizes: config.color_sizes,
+            tooltips: config.cell_tooltips,
+            zebra_stripes: config.zebra_stripes,
+            density: config.density,
+            favorites: config.favorites.clone(),
+            rules: config.rules.clone(),
+        }
+    }
 }
 
 impl WatchlistColumn {
-    fn new(kind: ColumnKind) -> Self {
+    /// The columns to show, in the saved order and widths; columns without a saved entry follow
+    /// in the default order.
+    fn all_from_config(config: &Config, options: &CellOptions) -> Vec<Self> {
+        let mut shown = ColumnKind::layout(config.merge_quote);
+        shown.retain(|kind| !config.hidden_columns.contains(kind));
+        let mut columns: Vec<Self> = Vec::with_capacity(shown.len());
+
+        let saved = config
+            .columns
+            .iter()
+            .map(|saved| (saved.kind, Some(saved.width)));
+        let defaults = shown.iter().map(|kind| (*kind, None));
+        for (kind, width) in saved.chain(defaults) {
+            if !shown.contains(&kind) || columns.iter().any(|column| column.kind == kind) {
+                continue;
+            }
+            let mut column = Self::new(kind, options.clone(), config.transform(kind));
+            column.precision = config.precision(kind);
+            column.number_style = config.number_style(kind);
+            if let Some(width) = width.filter(|width| width.is_finite() && *width > 0.0) {
+                column.width = column.clamp_width(width);
+            }
+            columns.push(column);
+        }
+        if let Some(first) = columns.first_mut() {
+            first.leading = true;
+        }
+
+        columns
+    }
+
+    fn new(kind: ColumnKind, options: CellOptions, transform: Transform) -> Self {
         let width = match kind {
             ColumnKind::Pair => 100.0,
             ColumnKind::MarkPrice => 100.0,
@@ -217,6 +3229,9 @@ impl WatchlistColumn {
             ColumnKind::BidSize => 100.0,
             ColumnKind::Ask => 100.0,
             ColumnKind::AskSize => 100.0,
+            ColumnKind::Quote => 200.0,
+            ColumnKind::Spread => 100.0,
+            ColumnKind::SpreadBps => 100.0,
             ColumnKind::OpenInterest => 100.0,
             ColumnKind::Open24h => 100.0,
             ColumnKind::High24h => 100.0,
@@ -224,20 +3239,68 @@ impl WatchlistColumn {
             ColumnKind::LastSize => 100.0,
             ColumnKind::FundingRate => 100.0,
             ColumnKind::FundingRatePrediction => 100.0,
+            ColumnKind::FundingRateAnnualized => 100.0,
+            ColumnKind::Premium => 100.0,
+            ColumnKind::Favorite => 40.0,
             ColumnKind::Suspended => 100.0,
             ColumnKind::IndexPrice => 100.0,
             ColumnKind::PostOnly => 100.0,
             ColumnKind::Change24h => 100.0,
+            ColumnKind::ComputedChange24h => 100.0,
+            ColumnKind::Sparkline => 100.0,
         };
 
         Self {
             kind,
             width,
             resize_offset: None,
+            options,
+            transform,
+            flashing: HashMap::new(),
+            frame: Instant::now(),
+            leading: false,
+            texts: HashMap::new(),
+            selected: None,
+            distribution: Vec::new(),
+            movers: HashMap::new(),
+            history: sparkline::History::new(),
+            sort: None,
+            precision: Precision::default(),
+            number_style: NumberStyle::default(),
+            now: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    /// Formats an already transformed numeric value for display.
+    fn format_number(&self, value: f64) -> String {
+        match (self.number_style, self.kind) {
+            (NumberStyle::Abbreviated, _) => {
+                format::abbreviate(value, self.options.abbrev_decimals)
+            }
+            (
+                NumberStyle::Grouped,
+                ColumnKind::Vol24h | ColumnKind::VolumeQuote | ColumnKind::OpenInterest,
+            ) => format::group_thousands(value, 0, self.options.separator),
+            // Sizes keep every digit they have, only grouped.
+            (NumberStyle::Grouped, _) => {
+                let decimals = value
+                    .to_string()
+                    .split_once('.')
+                    .map_or(0, |(_, fraction)| fraction.len());
+                format::group_thousands(value, decimals, self.options.separator)
+            }
+            (NumberStyle::Plain, kind) if kind.is_price() => self.precision.format(value),
+            (NumberStyle::Plain, kind) if kind.is_fraction() => format::percent(value),
+            (NumberStyle::Plain, _) => match self.precision {
+                // Sizes and counts have no magnitude heuristic, so they keep their digits.
+                Precision::Auto => value.to_string(),
+                precision => precision.format(value),
+            },
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum ColumnKind {
     Symbol,
     Last,
@@ -249,6 +3312,11 @@ enum ColumnKind {
     BidSize,
     Ask,
     AskSize,
+    /// Bid, ask and their sizes in one cell.
+    Quote,
+    /// Ask minus bid, in price units.
+    Spread,
+    SpreadBps,
     Vol24h,
     VolumeQuote,
     OpenInterest,
@@ -258,50 +3326,80 @@ enum ColumnKind {
     LastSize,
     FundingRate,
     FundingRatePrediction,
+    /// The current funding rate summed over a year of funding intervals.
+    FundingRateAnnualized,
+    /// How far the mark price sits above the index price, relative to the index.
+    Premium,
+    /// A star to toggle whether the market is a favorite.
+    Favorite,
     Suspended,
     IndexPrice,
     PostOnly,
     Change24h,
+    /// The 24h change in percent, recomputed from `last` and `open24h` instead of taken from
+    /// the feed.
+    ComputedChange24h,
+    /// A line of the mark price over the last polls.
+    Sparkline,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct WatchItem {
-    symbol: Option<String>,
-    last: Option<f64>,
-    last_time: Option<String>,
-    tag: Option<String>,
-    pair: Option<String>,
-    mark_price: Option<f64>,
-    bid: Option<f64>,
-    bid_size: Option<f64>,
-    ask: Option<f64>,
-    ask_size: Option<f64>,
-    vol24h: Option<f64>,
-    volume_quote: Option<f64>,
-    open_interest: Option<f64>,
-    open24h: Option<f64>,
-    high24h: Option<f64>,
-    low24h: Option<f64>,
-    last_size: Option<f64>,
-    funding_rate: Option<f64>,
-    funding_rate_prediction: Option<f64>,
-    suspended: Option<bool>,
-    index_price: Option<f64>,
-    post_only: Option<bool>,
-    change24h: Option<f64>,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-struct TickersResponse {
-    tickers: Vec<WatchItem>,
-}
+impl ColumnKind {
+    /// Every column, in the default display order.
+    const ALL: [ColumnKind; 31] = [
+        ColumnKind::Pair,
+        ColumnKind::Favorite,
+        ColumnKind::MarkPrice,
+        ColumnKind::Vol24h,
+        ColumnKind::VolumeQuote,
+        ColumnKind::Symbol,
+        ColumnKind::Last,
+        ColumnKind::LastTime,
+        ColumnKind::Tag,
+        ColumnKind::Quote,
+        ColumnKind::Bid,
+        ColumnKind::BidSize,
+        ColumnKind::Ask,
+        ColumnKind::AskSize,
+        ColumnKind::Spread,
+        ColumnKind::SpreadBps,
+        ColumnKind::OpenInterest,
+        ColumnKind::Open24h,
+        ColumnKind::High24h,
+        ColumnKind::Low24h,
+        ColumnKind::LastSize,
+        ColumnKind::FundingRate,
+        ColumnKind::FundingRatePrediction,
+        ColumnKind::FundingRateAnnualized,
+        ColumnKind::Premium,
+        ColumnKind::Suspended,
+        ColumnKind::IndexPrice,
+        ColumnKind::PostOnly,
+        ColumnKind::Change24h,
+        ColumnKind::ComputedChange24h,
+        ColumnKind::Sparkline,
+    ];
 
-impl<'a> table::Column<'a, Message, Theme, Renderer> for WatchlistColumn {
-    type Row = WatchItem;
+    /// The columns shown, in order: `ALL` with either the merged quote or its four parts.
+    fn layout(merge_quote: bool) -> Vec<ColumnKind> {
+        ColumnKind::ALL
+            .into_iter()
+            .filter(|kind| {
+                let quote_part = matches!(
+                    kind,
+                    ColumnKind::Bid | ColumnKind::BidSize | ColumnKind::Ask | ColumnKind::AskSize
+                );
+                if merge_quote {
+                    !quote_part
+                } else {
+                    *kind != ColumnKind::Quote
+                }
+            })
+            .collect()
+    }
 
-    fn header(&'a self, _col_index: usize) -> Element<'a, Message> {
-        let content = match self.kind {
+    /// The header text for the column.
+    fn label(&self) -> &'static str {
+        match self {
             ColumnKind::Pair => "MARKET",
             ColumnKind::MarkPrice => "PRICE",
             ColumnKind::Vol24h => "24H%",
@@ -314,6 +3412,9 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for WatchlistColumn {
             ColumnKind::BidSize => "B.SIZE",
             ColumnKind::Ask => "ASK",
             ColumnKind::AskSize => "A.SIZE",
+            ColumnKind::Quote => "BID × ASK (SIZE)",
+            ColumnKind::Spread => "SPREAD",
+            ColumnKind::SpreadBps => "SPR.BPS",
             ColumnKind::OpenInterest => "O.INTEREST",
             ColumnKind::Open24h => "O.24H",
             ColumnKind::High24h => "H.24H",
@@ -321,103 +3422,983 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for WatchlistColumn {
             ColumnKind::LastSize => "L.SIZE",
             ColumnKind::FundingRate => "F.RATE",
             ColumnKind::FundingRatePrediction => "F.R.PREDICTION",
+            ColumnKind::FundingRateAnnualized => "F.RATE/YR",
+            ColumnKind::Premium => "PREMIUM",
+            ColumnKind::Favorite => "★",
             ColumnKind::Suspended => "SUSPENDED",
             ColumnKind::IndexPrice => "I.PRICE",
             ColumnKind::PostOnly => "P.ONLY",
             ColumnKind::Change24h => "C.24H",
+            ColumnKind::ComputedChange24h => "C.24H*",
+            ColumnKind::Sparkline => "TREND",
+        }
+    }
+
+    /// The spelled-out name, for places with room for more than a header.
+    fn name(&self) -> &'static str {
+        match self {
+            ColumnKind::Pair => "Market",
+            ColumnKind::MarkPrice => "Mark price",
+            ColumnKind::Vol24h => "24h volume",
+            ColumnKind::VolumeQuote => "24h volume (quote)",
+            ColumnKind::Symbol => "Symbol",
+            ColumnKind::Last => "Last price",
+            ColumnKind::LastTime => "Last trade time",
+            ColumnKind::Tag => "Tag",
+            ColumnKind::Bid => "Bid",
+            ColumnKind::BidSize => "Bid size",
+            ColumnKind::Ask => "Ask",
+            ColumnKind::AskSize => "Ask size",
+            ColumnKind::Quote => "Bid × ask (size)",
+            ColumnKind::Spread => "Spread",
+            ColumnKind::SpreadBps => "Spread (bps)",
+            ColumnKind::OpenInterest => "Open interest",
+            ColumnKind::Open24h => "24h open",
+            ColumnKind::High24h => "24h high",
+            ColumnKind::Low24h => "24h low",
+            ColumnKind::LastSize => "Last trade size",
+            ColumnKind::FundingRate => "Funding rate",
+            ColumnKind::FundingRatePrediction => "Predicted funding rate",
+            ColumnKind::FundingRateAnnualized => "Annualized funding rate",
+            ColumnKind::Premium => "Premium over index",
+            ColumnKind::Favorite => "Favorite",
+            ColumnKind::Suspended => "Suspended",
+            ColumnKind::IndexPrice => "Index price",
+            ColumnKind::PostOnly => "Post only",
+            ColumnKind::Change24h => "24h change",
+            ColumnKind::ComputedChange24h => "24h change (computed)",
+            ColumnKind::Sparkline => "Mark price trend",
+        }
+    }
+
+    /// Whether rows can be ordered by this column. The quote, favorite and trend columns have
+    /// no single value to compare.
+    fn is_sortable(&self) -> bool {
+        !matches!(
+            self,
+            ColumnKind::Quote | ColumnKind::Favorite | ColumnKind::Sparkline
+        )
+    }
+
+    /// Whether the column holds numbers rather than text or flags.
+    fn is_numeric(&self) -> bool {
+        !matches!(
+            self,
+            ColumnKind::Symbol
+                | ColumnKind::LastTime
+                | ColumnKind::Tag
+                | ColumnKind::Pair
+                | ColumnKind::Suspended
+                | ColumnKind::PostOnly
+                | ColumnKind::Quote
+                | ColumnKind::Favorite
+                | ColumnKind::Sparkline
+        )
+    }
+
+    /// How cells line up: numbers to the right so digits align, flags centered, text to the left.
+    fn alignment(&self) -> iced::alignment::Horizontal {
+        match self {
+            ColumnKind::Suspended | ColumnKind::PostOnly | ColumnKind::Favorite => {
+                iced::alignment::Horizontal::Center
+            }
+            ColumnKind::Quote => iced::alignment::Horizontal::Right,
+            ColumnKind::Sparkline => iced::alignment::Horizontal::Center,
+            kind if kind.is_numeric() => iced::alignment::Horizontal::Right,
+            _ => iced::alignment::Horizontal::Left,
+        }
+    }
+
+    /// The narrowest the column can be resized to: room for a few characters, or for the whole
+    /// star or market name where cutting those off would leave nothing readable.
+    fn min_width(&self) -> f32 {
+        match self {
+            ColumnKind::Favorite => 30.0,
+            ColumnKind::Pair | ColumnKind::Symbol => 60.0,
+            ColumnKind::Quote => 120.0,
+            _ => 40.0,
+        }
+    }
+
+    /// Whether the column counts contracts or currency, where large numbers are common.
+    fn is_quantity(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::Vol24h
+                | ColumnKind::VolumeQuote
+                | ColumnKind::OpenInterest
+                | ColumnKind::BidSize
+                | ColumnKind::AskSize
+                | ColumnKind::LastSize
+        )
+    }
+
+    /// Whether the column holds a price, formatted with the column's `Precision`.
+    fn is_price(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::Last
+                | ColumnKind::MarkPrice
+                | ColumnKind::Bid
+                | ColumnKind::Ask
+                | ColumnKind::Spread
+                | ColumnKind::IndexPrice
+                | ColumnKind::Open24h
+                | ColumnKind::High24h
+                | ColumnKind::Low24h
+        )
+    }
+
+    /// Whether the column holds a fraction, shown as a percentage and colored by sign.
+    fn is_fraction(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::FundingRate
+                | ColumnKind::FundingRatePrediction
+                | ColumnKind::FundingRateAnnualized
+                | ColumnKind::Premium
+        )
+    }
+
+    /// The value behind a text or flag column, `None` for numeric columns or missing data.
+    fn text<'a>(&self, item: &'a WatchItem) -> Option<&'a str> {
+        let flag = |flag: Option<bool>| flag.map(|flag| if flag { "true" } else { "false" });
+
+        match self {
+            ColumnKind::Symbol => item.symbol.as_deref(),
+            ColumnKind::LastTime => item.last_time.as_deref(),
+            ColumnKind::Tag => item.tag.as_deref(),
+            ColumnKind::Pair => item.pair.as_deref(),
+            ColumnKind::Suspended => flag(item.suspended),
+            ColumnKind::PostOnly => flag(item.post_only),
+            _ => None,
+        }
+    }
+
+    /// The value behind a numeric column, `None` for text columns or missing data.
+    fn number(&self, item: &WatchItem) -> Option<f64> {
+        match self {
+            ColumnKind::Last => item.last,
+            ColumnKind::MarkPrice => item.mark_price,
+            ColumnKind::Bid => item.bid,
+            ColumnKind::BidSize => item.bid_size,
+            ColumnKind::Ask => item.ask,
+            ColumnKind::AskSize => item.ask_size,
+            ColumnKind::Spread => item.spread(),
+            ColumnKind::SpreadBps => item.spread_bps(),
+            ColumnKind::Vol24h => item.vol24h,
+            ColumnKind::VolumeQuote => item.volume_quote,
+            ColumnKind::OpenInterest => item.open_interest,
+            ColumnKind::Open24h => item.open24h,
+            ColumnKind::High24h => item.high24h,
+            ColumnKind::Low24h => item.low24h,
+            ColumnKind::LastSize => item.last_size,
+            ColumnKind::FundingRate => item.funding_rate,
+            ColumnKind::FundingRatePrediction => item.funding_rate_prediction,
+            ColumnKind::FundingRateAnnualized => {
+                item.funding_rate.map(|rate| rate * FUNDINGS_PER_YEAR)
+            }
+            ColumnKind::Premium => item.premium(),
+            ColumnKind::IndexPrice => item.index_price,
+            ColumnKind::Change24h => item.change24h,
+            ColumnKind::ComputedChange24h => item.change_since_open(),
+            ColumnKind::Symbol
+            | ColumnKind::LastTime
+            | ColumnKind::Tag
+            | ColumnKind::Pair
+            | ColumnKind::Suspended
+            | ColumnKind::PostOnly
+            | ColumnKind::Quote
+            | ColumnKind::Favorite
+            | ColumnKind::Sparkline => None,
+        }
+    }
+}
+
+impl fmt::Display for ColumnKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// The sortable column after `current` in `columns`, the first one when unsorted, or `None`
+/// past the last so cycling returns to the feed's order.
+fn next_sort_column(
+    mut columns: impl Iterator<Item = ColumnKind>,
+    current: Option<ColumnKind>,
+) -> Option<ColumnKind> {
+    if let Some(current) = current {
+        columns.find(|kind| *kind == current)?;
+    }
+    columns.find(ColumnKind::is_sortable)
+}
+
+/// The funding rate's annualized figure and the next predicted rate, for its tooltip.
+fn funding_tip(row: &WatchItem) -> String {
+    let rate = |kind: ColumnKind| {
+        kind.number(row)
+            .map_or("no data".to_string(), format::percent)
+    };
+
+    format!(
+        "Annualized: {}\nNext: {}",
+        rate(ColumnKind::FundingRateAnnualized),
+        rate(ColumnKind::FundingRatePrediction)
+    )
+}
+
+/// Green for gains and red for losses; no change keeps the theme's text color.
+fn change_color(change: f64) -> Option<Color> {
+    if change > 0.0 {
+        Some(GREEN)
+    } else if change < 0.0 {
+        Some(RED)
+    } else {
+        None
+    }
+}
+
+/// Fades a size cell from grey to bright cyan as its rank among the displayed sizes grows.
+fn size_color(rank: f64) -> Color {
+    let rank = rank.clamp(0.0, 1.0) as f32;
+    let mix = |from: f32, to: f32| from + (to - from) * rank;
+
+    Color::from_rgb(mix(GREY.r, 0.3), mix(GREY.g, 0.9), mix(GREY.b, 1.0))
+}
+
+/// Colors a relative spread by how tight it is.
+fn spread_color(bps: f64) -> Color {
+    if bps <= TIGHT_SPREAD_BPS {
+        GREEN
+    } else if bps <= WIDE_SPREAD_BPS {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+impl<'a> table::Column<'a, Message, Theme, Renderer> for WatchlistColumn {
+    type Row = TableRow;
+
+    fn header(&'a self, _col_index: usize) -> Element<'a, Message> {
+        let label = match self.sort {
+            Some(direction) => format!("{} {}", self.kind.label(), direction.glyph()),
+            None => self.kind.label().to_string(),
         };
+        let mut content = row![
+            button(text(label).size(self.options.density.text_size()))
+                .padding(0)
+                .style(button::text)
+                .on_press(Message::HeaderClicked(self.kind))
+        ]
+        .align_y(iced::Alignment::Center);
+        if self.kind.is_numeric() {
+            content = content.push(horizontal_space()).push(
+                button(text("⋮").size(14))
+                    .padding([0, 4])
+                    .style(button::text)
+                    .on_press(Message::ToggleColumnMenu(self.kind)),
+            );
+        }
 
-        container(text(content)).center_y(24).into()
+        container(content)
+            .center_y(self.options.density.header_height())
+            .into()
     }
 
     fn cell(
         &'a self,
         _col_index: usize,
-        _row_index: usize,
-        row: &'a WatchItem,
+        row_index: usize,
+        row: &'a TableRow,
     ) -> Element<'a, Message> {
-        let content: Element<_> = match self.kind {
-            ColumnKind::Symbol => text(row.symbol.clone().unwrap_or("N/A".to_string())).into(),
-            ColumnKind::Last => text(row.last.unwrap_or_default().to_string()).into(),
-            ColumnKind::LastTime => text(row.last_time.clone().unwrap_or("N/A".to_string())).into(),
-            ColumnKind::Tag => text(row.tag.clone().clone().unwrap_or("N/A".to_string())).into(),
-            ColumnKind::Pair => text(row.pair.clone().unwrap_or("N/A".to_string())).into(),
-            ColumnKind::MarkPrice => text(
-                row.mark_price
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
+        match row {
+            TableRow::Group(group) => self.group_cell(group),
+            TableRow::Market(item) => self.market_cell(row_index, item),
+        }
+    }
+
+    fn footer(&'a self, _col_index: usize, rows: &'a [Self::Row]) -> Option<Element<'a, Message>> {
+        let items: Vec<&WatchItem> = rows
+            .iter()
+            .filter_map(|row| match row {
+                TableRow::Market(item) => Some(item.as_ref()),
+                TableRow::Group(_) => None,
+            })
+            .collect();
+
+        let (content, alignment) = if self.leading {
+            (
+                format!("{} markets", items.len()),
+                iced::alignment::Horizontal::Left,
+            )
+        } else {
+            (self.aggregate(&items), self.kind.alignment())
+        };
+
+        Some(
+            container(
+                text(content)
+                    .size(self.options.density.text_size())
+                    .font(Font {
+                        weight: iced::font::Weight::Bold,
+                        ..Font::default()
+                    }),
             )
+            .width(Length::Fill)
+            .align_x(alignment)
             .into(),
-            ColumnKind::Bid => text(row.bid.map_or("N/A".to_string(), |v| format!("{}", v))).into(),
-            ColumnKind::BidSize => {
-                text(row.bid_size.map_or("N/A".to_string(), |v| format!("{}", v))).into()
-            }
-            ColumnKind::Ask => text(row.ask.map_or("N/A".to_string(), |v| format!("{}", v))).into(),
-            ColumnKind::AskSize => {
-                text(row.ask_size.map_or("N/A".to_string(), |v| format!("{}", v))).into()
-            }
-            ColumnKind::Vol24h => {
-                text(row.vol24h.map_or("N/A".to_string(), |v| format!("{}", v))).into()
-            }
-            ColumnKind::VolumeQuote => {
-                text(row.volume_quote.unwrap_or_default().to_string()).into()
-            }
-            ColumnKind::OpenInterest => {
-                text(row.open_interest.unwrap_or_default().to_string()).into()
+        )
+    }
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn resize_offset(&self) -> Option<f32> {
+        self.resize_offset
+    }
+}
+
+impl WatchlistColumn {
+    /// A group header line: the first column names the group, aggregate columns show its totals.
+    fn group_cell<'a>(&'a self, group: &'a Group) -> Element<'a, Message> {
+        let content: Element<'a, Message> = if self.leading {
+            let arrow = if group.collapsed { "▸" } else { "▾" };
+            button(
+                text(format!("{arrow} {} ({})", group.key, group.count))
+                    .size(self.options.density.text_size()),
+            )
+            .style(button::text)
+            .padding(0)
+            .on_press(Message::ToggleGroup(group.key.clone()))
+            .into()
+        } else {
+            let aggregate = match self.kind {
+                ColumnKind::Change24h => group.avg_change24h.map(|avg| format!("avg {avg:.2}")),
+                ColumnKind::Vol24h => Some(self.format_number(group.vol24h)),
+                ColumnKind::VolumeQuote => Some(self.format_number(group.volume_quote)),
+                _ => None,
+            };
+            text(aggregate.unwrap_or_default())
+                .size(self.options.density.text_size())
+                .into()
+        };
+
+        // The group name spills across from the first column, so only aggregates are aligned.
+        let alignment = if self.leading {
+            iced::alignment::Horizontal::Left
+        } else {
+            self.kind.alignment()
+        };
+
+        container(content)
+            .width(Length::Fill)
+            .center_y(self.options.density.row_height())
+            .align_x(alignment)
+            .style(|theme: &Theme| container::Style {
+                background: Some(theme.extended_palette().background.weak.color.into()),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// The footer summary of this column over `items`: totals for volumes, the average change,
+    /// and "N/A" for columns where neither means anything.
+    fn aggregate(&self, items: &[&WatchItem]) -> String {
+        let values: Vec<f64> = items
+            .iter()
+            .filter_map(|item| self.kind.number(item))
+            .filter(|value| !value.is_nan())
+            .collect();
+        if values.is_empty() {
+            return "N/A".to_string();
+        }
+        let sum: f64 = values.iter().sum();
+
+        match self.kind {
+            ColumnKind::Vol24h | ColumnKind::VolumeQuote | ColumnKind::OpenInterest => {
+                self.format_number(sum)
             }
-            ColumnKind::Open24h => {
-                text(row.open24h.map_or("N/A".to_string(), |v| format!("{}", v))).into()
+            ColumnKind::Change24h | ColumnKind::ComputedChange24h => self
+                .options
+                .change_display
+                .format(Some(sum / values.len() as f64), None)
+                .map_or("N/A".to_string(), |avg| format!("avg {avg}")),
+            _ => "N/A".to_string(),
+        }
+    }
+
+    /// Where a bid or ask size falls among the displayed ones, from 0 (smallest) to 1 (largest).
+    fn size_rank(&self, row: &WatchItem) -> Option<f64> {
+        if !self.options.color_sizes
+            || !matches!(self.kind, ColumnKind::BidSize | ColumnKind::AskSize)
+        {
+            return None;
+        }
+        let value = self.kind.number(row).filter(|value| !value.is_nan())?;
+        let below = self.distribution.partition_point(|other| *other < value);
+
+        Some(if self.distribution.len() > 1 {
+            below as f64 / (self.distribution.len() - 1) as f64
+        } else {
+            1.0
+        })
+    }
+
+    /// The unrounded value behind a numeric cell, plus the raw one when a transform changes it.
+    fn precise_value(&self, row: &WatchItem) -> Option<String> {
+        let raw = self.kind.number(row).filter(|_| self.kind.is_numeric())?;
+        if self.transform.is_identity() {
+            Some(raw.to_string())
+        } else {
+            Some(format!("{} (raw {raw})", self.transform.apply(raw)))
+        }
+    }
+
+    /// "bid (size) × ask (size)", with each side in its own color unless a rule overrides it.
+    fn quote_content(&self, row: &WatchItem, color: Option<Color>) -> Element<'_, Message> {
+        let side = |price, size| self.quote_side(price, size);
+        let font = if self.options.monospace_numbers {
+            Font::MONOSPACE
+        } else {
+            Font::default()
+        };
+
+        let size = self.options.density.text_size();
+
+        row![
+            text(side(row.bid, row.bid_size))
+                .size(size)
+                .font(font)
+                .color(color.unwrap_or(GREEN)),
+            text("×").size(size).color(GREY),
+            text(side(row.ask, row.ask_size))
+                .size(size)
+                .font(font)
+                .color(color.unwrap_or(RED)),
+        ]
+        .spacing(6)
+        .into()
+    }
+
+    /// One side of the merged quote: "price (size)".
+    fn quote_side(&self, price: Option<f64>, size: Option<f64>) -> String {
+        let price = price.map_or("N/A".to_string(), |price| self.precision.format(price));
+        match size {
+            Some(size) => format!("{price} ({})", self.format_number(size)),
+            None => price,
+        }
+    }
+
+    /// The text shown in this column for `row`.
+    fn cell_text(&self, row: &WatchItem) -> String {
+        if self.kind == ColumnKind::Quote {
+            format!(
+                "{} × {}",
+                self.quote_side(row.bid, row.bid_size),
+                self.quote_side(row.ask, row.ask_size)
+            )
+        } else if let (ColumnKind::Change24h | ColumnKind::ComputedChange24h, true) =
+            (self.kind, self.transform.is_identity())
+        {
+            // A custom transform on the change column takes over from the change display mode.
+            let percent = self.kind.number(row);
+            // Without the feed's figure the absolute change stands in, but a computed change
+            // that can't be worked out has nothing meaningful to fall back on.
+            let absolute = match self.kind {
+                ColumnKind::ComputedChange24h => percent.and(row.change_absolute()),
+                _ => row.change_absolute(),
+            };
+            self.options
+                .change_display
+                .format(percent, absolute)
+                .unwrap_or("N/A".to_string())
+        } else if self.kind.is_numeric() {
+            self.kind.number(row).map_or("N/A".to_string(), |v| {
+                let formatted = self.format_number(self.transform.apply(v));
+                format!("{formatted}{}", self.transform.suffix)
+            })
+        } else if let (ColumnKind::LastTime, Some(at)) = (self.kind, row.last_traded_at()) {
+            clock::age(at, self.now)
+        } else if self.kind == ColumnKind::Sparkline {
+            String::new()
+        } else {
+            let content = self.kind.text(row).unwrap_or("N/A");
+            if self.kind == ColumnKind::Pair && self.options.highlight_crossed && row.is_crossed() {
+                format!("⚠ {content}")
+            } else {
+                content.to_string()
             }
-            ColumnKind::High24h => {
-                text(row.high24h.map_or("N/A".to_string(), |v| format!("{}", v))).into()
+        }
+    }
+
+    /// A width wide enough for the header and every displayed cell, estimated from character
+    /// counts since text can't be measured outside the renderer.
+    fn fitted_width(&self) -> f32 {
+        let glyph_width = if self.options.monospace_numbers && self.kind.is_numeric() {
+            MONOSPACE_GLYPH_WIDTH
+        } else {
+            AVERAGE_GLYPH_WIDTH
+        } * self.options.density.text_size()
+            / Density::Normal.text_size();
+        // Room for the sort arrow, and on numeric columns the menu button.
+        let header_extra = if self.kind.is_numeric() { 4 } else { 2 };
+        let chars = self
+            .texts
+            .values()
+            .map(|text| text.chars().count())
+            .chain([self.kind.label().chars().count() + header_extra])
+            .max()
+            .unwrap_or_default();
+
+        (chars as f32 * glyph_width + AUTOFIT_PADDING)
+            .clamp(self.kind.min_width(), AUTOFIT_MAX_WIDTH)
+    }
+
+    /// Keeps a width between the kind's minimum and `MAX_COLUMN_WIDTH`, so a column dragged far
+    /// left stays visible and can be grabbed again. A NaN width gets the minimum.
+    fn clamp_width(&self, width: f32) -> f32 {
+        let min = self.kind.min_width();
+        if width.is_nan() {
+            return min;
+        }
+
+        width.clamp(min, MAX_COLUMN_WIDTH)
+    }
+
+    /// Formats every row's cell up front, so redraws borrow the text instead of rebuilding it.
+    fn refresh_texts(&mut self, rows: &[WatchItem]) {
+        self.texts = rows
+            .iter()
+            .filter_map(|row| Some((row.symbol.clone()?, self.cell_text(row))))
+            .collect();
+    }
+
+    /// The cached text for `row`, or freshly formatted text for a row the cache doesn't know.
+    fn display_text(&self, row: &WatchItem) -> Cow<'_, str> {
+        match row
+            .symbol
+            .as_ref()
+            .and_then(|symbol| self.texts.get(symbol))
+        {
+            Some(cached) => Cow::Borrowed(cached),
+            None => Cow::Owned(self.cell_text(row)),
+        }
+    }
+
+    fn market_cell<'a>(&'a self, row_index: usize, row: &'a WatchItem) -> Element<'a, Message> {
+        let mut content = text(self.display_text(row)).size(self.options.density.text_size());
+        if self.options.monospace_numbers && self.kind.is_numeric() {
+            content = content.font(Font::MONOSPACE);
+        }
+        if let (ColumnKind::SpreadBps, Some(bps)) = (self.kind, row.spread_bps()) {
+            content = content.color(spread_color(bps));
+        }
+        if self.kind == ColumnKind::Spread
+            && row.has_wide_spread(self.options.wide_spread_threshold)
+        {
+            content = content.color(RED);
+        }
+        if let Some(rank) = self.size_rank(row) {
+            content = content.color(size_color(rank));
+            if rank >= LARGE_SIZE_RANK {
+                content = content.font(Font {
+                    weight: iced::font::Weight::Bold,
+                    ..if self.options.monospace_numbers {
+                        Font::MONOSPACE
+                    } else {
+                        Font::default()
+                    }
+                });
             }
-            ColumnKind::Low24h => {
-                text(row.low24h.map_or("N/A".to_string(), |v| format!("{}", v))).into()
+        }
+        let change = match self.kind {
+            ColumnKind::Change24h => row.change24h.or_else(|| row.change_absolute()),
+            ColumnKind::ComputedChange24h => row.change_since_open(),
+            ColumnKind::Vol24h => row.change24h,
+            kind if kind.is_fraction() => kind.number(row),
+            _ => None,
+        };
+        if let Some(color) = change.and_then(change_color) {
+            content = content.color(color);
+        }
+
+        let mut background =
+            (self.options.highlight_crossed && row.is_crossed()).then_some(CROSSED_BACKGROUND);
+        if let Some(tag) = row
+            .symbol
+            .as_ref()
+            .and_then(|symbol| self.movers.get(symbol))
+        {
+            background = Some(match tag {
+                RankTag::Gainer => GAINER_BACKGROUND,
+                RankTag::Loser => LOSER_BACKGROUND,
+            });
+        }
+        if let Some(rule) = rules::matching(&self.options.rules, self.kind, row) {
+            if rule.background {
+                background = Some(Color {
+                    a: 0.35,
+                    ..rule.color.color()
+                });
+            } else {
+                content = content.color(rule.color.color());
             }
-            ColumnKind::LastSize => text(
-                row.last_size
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
+        }
+
+        let content: Element<'a, Message> = if self.kind == ColumnKind::Quote {
+            let rule_color = rules::matching(&self.options.rules, self.kind, row)
+                .filter(|rule| !rule.background)
+                .map(|rule| rule.color.color());
+            self.quote_content(row, rule_color)
+        } else if let (ColumnKind::Favorite, Some(symbol)) = (self.kind, &row.symbol) {
+            let starred = self.options.favorites.contains(symbol);
+            button(
+                text(if starred { "★" } else { "☆" })
+                    .size(self.options.density.text_size())
+                    .color_maybe(starred.then_some(YELLOW)),
             )
-            .into(),
-            ColumnKind::FundingRate => {
-                text(row.funding_rate.unwrap_or_default().to_string()).into()
-            }
-            ColumnKind::FundingRatePrediction => {
-                text(row.funding_rate_prediction.unwrap_or_default().to_string()).into()
+            .style(button::text)
+            .padding(0)
+            .on_press(Message::ToggleFavorite(symbol.clone()))
+            .into()
+        } else if self.kind == ColumnKind::Sparkline {
+            match row
+                .symbol
+                .as_ref()
+                .and_then(|symbol| self.history.get(symbol))
+            {
+                Some(samples) => canvas(Sparkline::new(samples))
+                    .width(Length::Fill)
+                    .height(self.options.density.row_height() * 0.6)
+                    .into(),
+                None => content.into(),
             }
-            ColumnKind::Suspended => text(
-                row.suspended
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
-            )
-            .into(),
-            ColumnKind::IndexPrice => text(
-                row.index_price
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
-            )
-            .into(),
-            ColumnKind::PostOnly => text(
-                row.post_only
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
-            )
-            .into(),
-            ColumnKind::Change24h => text(
-                row.change24h
-                    .map_or("N/A".to_string(), |v| format!("{}", v)),
+        } else {
+            content.into()
+        };
+
+        if row.symbol.is_some() && row.symbol == self.selected {
+            background = Some(SELECTED_BACKGROUND);
+        }
+        if let Some(flash) = row
+            .symbol
+            .as_ref()
+            .and_then(|symbol| self.flashing.get(symbol))
+        {
+            background = Some(Color {
+                a: 0.3 * flash.strength(self.frame, FLASH_DURATION),
+                ..if flash.rose { GREEN } else { RED }
+            });
+        }
+
+        // Any other highlight takes over from the stripe, which is only there to guide the eye.
+        let striped = self.options.zebra_stripes && row_index % 2 == 1;
+        let cell = container(content)
+            .width(Length::Fill)
+            .center_y(self.options.density.row_height())
+            .align_x(self.kind.alignment())
+            .style(move |theme: &Theme| container::Style {
+                background: background
+                    .or_else(|| {
+                        striped.then(|| Color {
+                            a: 0.5,
+                            ..theme.extended_palette().background.weak.color
+                        })
+                    })
+                    .map(Into::into),
+                ..Default::default()
+            });
+        // Funding always explains itself on hover, the precise value only when asked for.
+        let tip = if self.kind == ColumnKind::FundingRate {
+            Some(funding_tip(row))
+        } else {
+            self.precise_value(row).filter(|_| self.options.tooltips)
+        };
+        let cell: Element<'a, Message> = match tip {
+            Some(tip) => tooltip(
+                cell,
+                container(text(tip).size(12).font(Font::MONOSPACE))
+                    .padding(6)
+                    .style(container::rounded_box),
+                tooltip::Position::Top,
             )
             .into(),
+            _ => cell.into(),
         };
 
-        container(content).width(Length::Fill).center_y(32).into()
+        match &row.symbol {
+            Some(symbol) => mouse_area(cell)
+                .on_press(Message::RowClicked(symbol.clone()))
+                .on_right_press(Message::OpenRowMenu(symbol.clone()))
+                .into(),
+            None => cell,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(symbol: &str, last: f64) -> WatchItem {
+        WatchItem {
+            symbol: Some(symbol.to_string()),
+            last: Some(last),
+            ..Default::default()
+        }
     }
 
-    fn footer(&'a self, _col_index: usize, _rows: &'a [Self::Row]) -> Option<Element<'a, Message>> {
-        Some(horizontal_space().into())
+    #[test]
+    fn merge_keeps_existing_order() {
+        let mut current = vec![
+            ticker("PF_A", 1.0),
+            ticker("PF_B", 2.0),
+            ticker("PF_C", 3.0),
+        ];
+        let fetched = vec![
+            ticker("PF_D", 40.0),
+            ticker("PF_C", 30.0),
+            ticker("PF_A", 10.0),
+        ];
+
+        merge_tickers(&mut current, fetched);
+
+        let merged: Vec<(Option<&str>, Option<f64>)> = current
+            .iter()
+            .map(|item| (item.symbol.as_deref(), item.last))
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                (Some("PF_A"), Some(10.0)),
+                (Some("PF_C"), Some(30.0)),
+                (Some("PF_D"), Some(40.0)),
+            ]
+        );
     }
 
-    fn width(&self) -> f32 {
-        self.width
+    #[test]
+    fn computed_change_from_open() {
+        let moved = |last, open24h| WatchItem {
+            last,
+            open24h,
+            change24h: Some(99.0),
+            ..Default::default()
+        };
+        let column = WatchlistColumn::new(
+            ColumnKind::ComputedChange24h,
+            CellOptions::default(),
+            Transform::default(),
+        );
+
+        assert_eq!(
+            column.cell_text(&moved(Some(110.0), Some(100.0))),
+            "+10.00%"
+        );
+        assert_eq!(column.cell_text(&moved(Some(95.0), Some(100.0))), "-5.00%");
+        assert_eq!(column.cell_text(&moved(Some(95.0), Some(0.0))), "N/A");
+        assert_eq!(column.cell_text(&moved(None, Some(100.0))), "N/A");
+        assert_eq!(column.cell_text(&moved(Some(95.0), None)), "N/A");
     }
 
-    fn resize_offset(&self) -> Option<f32> {
-        self.resize_offset
+    #[test]
+    fn decimals_apply_to_columns_without_their_own() {
+        let mut config = Config {
+            decimals: Precision::Fixed(2),
+            ..Config::default()
+        };
+        config
+            .precision
+            .insert(ColumnKind::Bid, Precision::Fixed(4));
+        let cell = |config: &Config, kind| {
+            let mut column =
+                WatchlistColumn::new(kind, CellOptions::from_config(config), Transform::default());
+            column.precision = config.precision(kind);
+            column.number_style = config.number_style(kind);
+            column.cell_text(&WatchItem {
+                mark_price: Some(104215.123),
+                bid: Some(104215.123),
+                bid_size: Some(0.0125),
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(cell(&config, ColumnKind::MarkPrice), "104215.12");
+        assert_eq!(cell(&config, ColumnKind::Bid), "104215.1230");
+        assert_eq!(cell(&config, ColumnKind::BidSize), "0.01");
+
+        config.decimals = Precision::Auto;
+        assert_eq!(cell(&config, ColumnKind::MarkPrice), "104215.12");
+        assert_eq!(cell(&config, ColumnKind::BidSize), "0.0125");
+    }
+
+    #[test]
+    fn launch_overrides_are_not_saved() {
+        let mut config = Config::default();
+        let stored = serde_json::to_value(&config).unwrap();
+        config.launch = LaunchOverrides {
+            refresh_interval: Some(Duration::from_secs(10)),
+            theme: Some(ThemeChoice::Explicit(ThemeMode::Dracula)),
+        };
+
+        assert_eq!(config.refresh_interval(), Duration::from_secs(10));
+        assert_eq!(
+            config.theme_choice(),
+            ThemeChoice::Explicit(ThemeMode::Dracula)
+        );
+        // What `save` writes, so the next launch starts from the stored settings again.
+        assert_eq!(serde_json::to_value(&config).unwrap(), stored);
+        let saved: Config = serde_json::from_value(stored).unwrap();
+        assert_eq!(saved.refresh_interval(), Config::default().refresh_interval);
+        assert_eq!(saved.theme_choice(), ThemeChoice::System);
+    }
+
+    #[test]
+    fn precisions_follow_the_data_source() {
+        let mut config = Config::default();
+        config
+            .precision
+            .insert(ColumnKind::MarkPrice, Precision::Fixed(1));
+
+        // Spot has no preset yet, so the futures precisions carry over.
+        config.switch_data_source(DataSource::Spot);
+        assert_eq!(config.precision(ColumnKind::MarkPrice), Precision::Fixed(1));
+
+        config.decimals = Precision::Fixed(6);
+        config.precision.clear();
+        config.switch_data_source(DataSource::Futures);
+        assert_eq!(config.decimals, Precision::Auto);
+        assert_eq!(config.precision(ColumnKind::MarkPrice), Precision::Fixed(1));
+
+        config.switch_data_source(DataSource::Spot);
+        assert_eq!(config.data_source, DataSource::Spot);
+        assert_eq!(config.precision(ColumnKind::MarkPrice), Precision::Fixed(6));
+    }
+
+    #[test]
+    fn cached_cells_draw_without_formatting() {
+        let rows = [ticker("PF_A", 1.5), ticker("PF_B", 2.5)];
+        let mut column = WatchlistColumn::new(
+            ColumnKind::Last,
+            CellOptions::default(),
+            Transform::default(),
+        );
+        assert!(matches!(column.display_text(&rows[0]), Cow::Owned(_)));
+
+        column.refresh_texts(&rows[..1]);
+        let cached = column.texts["PF_A"].as_ptr();
+        // Every redraw borrows the same formatted string rather than building a new one.
+        for _ in 0..3 {
+            let text = column.display_text(&rows[0]);
+            assert!(matches!(text, Cow::Borrowed(_)));
+            assert_eq!(text.as_ptr(), cached);
+        }
+        assert_eq!(column.display_text(&rows[1]), column.cell_text(&rows[1]));
+    }
+
+    #[test]
+    fn sort_cycling_skips_unsortable_columns() {
+        let columns = [
+            ColumnKind::Favorite,
+            ColumnKind::Symbol,
+            ColumnKind::Sparkline,
+            ColumnKind::Quote,
+            ColumnKind::MarkPrice,
+            ColumnKind::Sparkline,
+        ];
+        let mut visited = vec![];
+        let mut sort = None;
+        loop {
+            sort = next_sort_column(columns.into_iter(), sort);
+            let Some(kind) = sort else { break };
+            visited.push(kind);
+        }
+
+        assert_eq!(visited, [ColumnKind::Symbol, ColumnKind::MarkPrice]);
+        assert_eq!(
+            next_sort_column(columns.into_iter(), None),
+            Some(ColumnKind::Symbol)
+        );
+    }
+
+    #[test]
+    fn resize_is_clamped() {
+        let column =
+            |kind| WatchlistColumn::new(kind, CellOptions::default(), Transform::default());
+        let last = column(ColumnKind::Last);
+
+        assert_eq!(last.clamp_width(100.0 - 10_000.0), 40.0);
+        assert_eq!(last.clamp_width(100.0 + 1e9), MAX_COLUMN_WIDTH);
+        assert_eq!(last.clamp_width(f32::NEG_INFINITY), 40.0);
+        assert_eq!(last.clamp_width(f32::NAN), 40.0);
+        assert_eq!(last.clamp_width(150.0), 150.0);
+        assert_eq!(column(ColumnKind::Quote).clamp_width(0.0), 120.0);
+    }
+
+    #[test]
+    fn movers_rank_both_ends() {
+        let changed = |symbol: &str, change24h: f64| WatchItem {
+            symbol: Some(symbol.to_string()),
+            change24h: Some(change24h),
+            ..Default::default()
+        };
+        let rows = [
+            changed("PF_A", 5.0),
+            changed("PF_B", -3.0),
+            changed("PF_C", 1.0),
+            changed("PF_D", -0.5),
+            changed("PF_E", f64::NAN),
+        ];
+
+        let movers = summary::movers(&rows, 1);
+        assert_eq!(movers.len(), 2);
+        assert_eq!(movers.get("PF_A"), Some(&RankTag::Gainer));
+        assert_eq!(movers.get("PF_B"), Some(&RankTag::Loser));
+        // With more slots than movers either way, flat and opposite moves stay out.
+        let movers = summary::movers(&[changed("PF_A", 5.0), changed("PF_C", 0.0)], 3);
+        assert_eq!(movers.len(), 1);
+    }
+
+    #[test]
+    fn live_markets_favor_favorites_then_displayed_rows() {
+        let item = |symbol: &str| WatchItem {
+            symbol: Some(symbol.to_string()),
+            ..Default::default()
+        };
+        let watch_list = vec![item("A"), item("B"), item("C"), item("D")];
+        // Filtered down to C and B, in that order, with the favorite D hidden.
+        let rows = vec![item("C"), item("B")];
+        let favorites = HashSet::from(["D".to_string()]);
+
+        assert_eq!(
+            live_markets(&watch_list, &rows, &favorites, 3),
+            ["D", "C", "B"]
+        );
+        assert_eq!(
+            live_markets(&watch_list, &rows, &favorites, 10),
+            ["D", "C", "B", "A"]
+        );
+        assert!(live_markets(&watch_list, &rows, &favorites, 0).is_empty());
+    }
+
+    #[test]
+    fn stale_fetches_are_dropped() {
+        let mut fetches = Fetches::default();
+        let older = fetches.start();
+        let newer = fetches.start();
+
+        // The newer response lands first and wins; the older one is then ignored.
+        assert!(fetches.finish(newer));
+        assert!(!fetches.in_flight);
+        assert!(!fetches.finish(older));
+
+        // Out of order the other way round, the older response doesn't end the newer fetch.
+        let older = fetches.start();
+        let newer = fetches.start();
+        assert!(!fetches.finish(older));
+        assert!(fetches.in_flight);
+        assert!(fetches.finish(newer));
     }
 }
@@ -0,0 +1,8 @@
+use krader::data::{WatchItem, parse_tickers};
+
+const SAMPLE: &str = include_str!("../assets/mock_tickers.json");
+
+/// A fixed snapshot of the tickers endpoint, for working without a connection.
+pub fn tickers() -> Vec<WatchItem> {
+    parse_tickers(SAMPLE).expect("bundled mock tickers are valid")
+}
@@ -0,0 +1,317 @@
+use crate::format::ChangeDisplay;
+use crate::grouping::Grouping;
+use crate::theme::{Density, ThemeChoice};
+use crate::{ColumnKind, DEFAULT_MOVER_COUNT, Krader, Message};
+use krader::source::DataSource;
+
+/// How many matches the palette lists at once.
+pub const MAX_RESULTS: usize = 12;
+
+/// An action the palette can run.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub title: String,
+    pub message: Message,
+}
+
+impl Command {
+    fn new(title: impl Into<String>, message: Message) -> Self {
+        Self {
+            title: title.into(),
+            message,
+        }
+    }
+}
+
+/// Every action available in the current state. Toggles are listed under the state they switch
+/// to, so the titles read as what running them will do.
+pub fn commands(app: &Krader) -> Vec<Command> {
+    let config = &app.config;
+    let toggle =
+        |enabled: bool, what: &str| format!("{} {what}", if enabled { "Hide" } else { "Show" });
+    let switch = |enabled: bool, what: &str| {
+        format!("{} {what}", if enabled { "Disable" } else { "Enable" })
+    };
+
+    let mut commands = vec![
+        Command::new("Refresh now", Message::FetchData),
+        Command::new(
+            if app.is_paused {
+                "Resume live updates"
+            } else {
+                "Pause live updates"
+            },
+            Message::TogglePause,
+        ),
+        Command::new("Copy table image", Message::CopyTableImage),
+        match config.data_source {
+            DataSource::Futures => Command::new(
+                "Switch to spot markets",
+                Message::DataSourceChanged(DataSource::Spot),
+            ),
+            DataSource::Spot => Command::new(
+                "Switch to futures markets",
+                Message::DataSourceChanged(DataSource::Futures),
+            ),
+        },
+        Command::new(
+            toggle(app.show_help, "keyboard shortcuts"),
+            Message::ToggleHelp,
+        ),
+        Command::new(
+            toggle(app.show_settings, "settings"),
+            Message::ToggleSettings,
+        ),
+        Command::new(
+            toggle(app.show_histogram, "histogram"),
+            Message::ToggleHistogram,
+        ),
+        Command::new(
+            toggle(app.rule_draft.is_some(), "rules editor"),
+            Message::ToggleRulesEditor,
+        ),
+        Command::new("Sort by next column", Message::CycleSortColumn),
+        Command::new("Reverse sort direction", Message::ToggleSortDirection),
+        if app.frozen_order.is_some() {
+            Command::new("Unfreeze row order", Message::UnfreezeOrder)
+        } else {
+            Command::new("Freeze row order", Message::FreezeOrder)
+        },
+        Command::new("Clear all filters", Message::ClearAllFilters),
+        Command::new(
+            switch(app.filters.crossed_only, "crossed only filter"),
+            Message::CrossedOnlyToggled(!app.filters.crossed_only),
+        ),
+        Command::new(
+            switch(app.filters.hide_suspended, "hide suspended filter"),
+            Message::HideSuspendedToggled(!app.filters.hide_suspended),
+        ),
+        Command::new(
+            switch(config.allowlist_only, "allowlist filter"),
+            Message::AllowlistOnlyToggled(!config.allowlist_only),
+        ),
+        Command::new(
+            switch(config.highlight_movers.is_some(), "top movers highlighting"),
+            Message::HighlightMoversChanged(
+                config
+                    .highlight_movers
+                    .is_none()
+                    .then_some(DEFAULT_MOVER_COUNT),
+            ),
+        ),
+        Command::new(
+            switch(config.highlight_crossed, "crossed highlighting"),
+            Message::HighlightCrossedToggled(!config.highlight_crossed),
+        ),
+        Command::new(
+            switch(config.whole_volumes, "whole volumes"),
+            Message::WholeVolumesToggled(!config.whole_volumes),
+        ),
+        Command::new(
+            switch(config.abbreviate_volumes, "abbreviated volumes"),
+            Message::AbbreviateVolumesToggled(!config.abbreviate_volumes),
+        ),
+        Command::new(
+            switch(config.monospace_numbers, "monospace numbers"),
+            Message::MonospaceNumbersToggled(!config.monospace_numbers),
+        ),
+        Command::new(
+            switch(config.color_sizes, "size coloring"),
+            Message::ColorSizesToggled(!config.color_sizes),
+        ),
+        Command::new(
+            switch(config.merge_quote, "merged quote column"),
+            Message::MergeQuoteToggled(!config.merge_quote),
+        ),
+        Command::new(
+            switch(config.pin_first_column, "pinned first column"),
+            Message::PinFirstColumnToggled(!config.pin_first_column),
+        ),
+        Command::new(
+            switch(config.resizable_columns, "resizable columns"),
+            Message::ResizableColumnsToggled(!config.resizable_columns),
+        ),
+        Command::new(
+            toggle(config.show_footer, "totals row"),
+            Message::FooterToggled(!config.show_footer),
+        ),
+        Command::new(
+            switch(config.fill_width, "full-width table"),
+            Message::FillWidthToggled(!config.fill_width),
+        ),
+        Command::new(
+            switch(config.show_mover_banner, "biggest mover banner"),
+            Message::MoverBannerToggled(!config.show_mover_banner),
+        ),
+        Command::new(
+            switch(config.live_feed, "live updates"),
+            Message::LiveFeedToggled(!config.live_feed),
+        ),
+        Command::new(
+            switch(config.cell_tooltips, "value tooltips"),
+            Message::CellTooltipsToggled(!config.cell_tooltips),
+        ),
+        Command::new(
+            switch(config.zebra_stripes, "striped rows"),
+            Message::ZebraStripesToggled(!config.zebra_stripes),
+        ),
+        Command::new(
+            switch(config.density == Density::Compact, "compact rows"),
+            Message::CompactRowsToggled(config.density != Density::Compact),
+        ),
+        if config.outer_padding == 0 {
+            Command::new("Padded layout", Message::OuterPaddingChanged(20))
+        } else {
+            Command::new("Compact layout", Message::OuterPaddingChanged(0))
+        },
+        if app.mock_data {
+            Command::new("Go online", Message::UseMockData(false))
+        } else {
+            Command::new("Work offline with mock data", Message::UseMockData(true))
+        },
+    ];
+
+    commands.extend(
+        ColumnKind::layout(config.merge_quote)
+            .into_iter()
+            .map(|kind| {
+                let shown = !config.hidden_columns.contains(&kind);
+                Command::new(
+                    toggle(shown, &format!("{} column", kind.name().to_lowercase())),
+                    Message::ToggleColumn(kind),
+                )
+            }),
+    );
+    commands.extend(ThemeChoice::ALL.into_iter().map(|choice| {
+        Command::new(
+            format!("Theme: {choice}"),
+            Message::ThemeChoiceChanged(choice),
+        )
+    }));
+    commands.extend(
+        Grouping::ALL
+            .into_iter()
+            .map(|grouping| Command::new(grouping.to_string(), Message::GroupingChanged(grouping))),
+    );
+    commands.extend(ChangeDisplay::ALL.into_iter().map(|display| {
+        Command::new(
+            format!("24h change: {display}"),
+            Message::ChangeDisplayChanged(display),
+        )
+    }));
+    commands.extend(
+        app.columns
+            .iter()
+            .filter(|column| column.kind.is_numeric())
+            .map(|column| {
+                Command::new(
+                    format!("Edit {} column", column.kind.label()),
+                    Message::ToggleColumnMenu(column.kind),
+                )
+            }),
+    );
+    commands
+}
+
+/// The commands matching `query`, best first.
+pub fn search(commands: Vec<Command>, query: &str) -> Vec<Command> {
+    let mut scored: Vec<(i32, Command)> = commands
+        .into_iter()
+        .filter_map(|command| Some((score(query, &command.title)?, command)))
+        .collect();
+    // Stable, so equally good matches keep registry order.
+    scored.sort_by_key(|(score, _)| -score);
+
+    scored
+        .into_iter()
+        .map(|(_, command)| command)
+        .take(MAX_RESULTS)
+        .collect()
+}
+
+/// Fuzzy match: every query character must appear in `title` in order, ignoring case.
+///
+/// Consecutive matches and matches at word starts score higher, gaps cost a little.
+fn score(query: &str, title: &str) -> Option<i32> {
+    let title: Vec<char> = title.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        let found = position + title[position..].iter().position(|c| *c == wanted)?;
+
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !title[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position) as i32 / 4;
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(commands: &[Command]) -> Vec<&str> {
+        commands
+            .iter()
+            .map(|command| command.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn query_characters_match_in_order_ignoring_case() {
+        assert!(score("rfn", "Refresh now").is_some());
+        assert!(score("REFRESH", "Refresh now").is_some());
+        assert!(score("nr", "Refresh now").is_none());
+        assert!(score("refresh later", "Refresh now").is_none());
+        assert_eq!(score("", "Refresh now"), Some(0));
+    }
+
+    #[test]
+    fn runs_and_word_starts_score_higher() {
+        // Both hits of "re" land together at a word start in "Refresh", apart in "Rules editor".
+        assert_eq!(score("re", "Refresh"), Some(10));
+        assert_eq!(score("re", "Rules editor"), Some(5));
+        // A word start beats the same letter mid-word.
+        assert_eq!(score("e", "Edit"), Some(4));
+        assert_eq!(score("e", "Reset"), Some(1));
+        // Long gaps cost a little.
+        assert_eq!(score("n", "Resume fetching"), Some(-2));
+    }
+
+    #[test]
+    fn results_are_capped_and_ties_keep_registry_order() {
+        let commands: Vec<Command> = (0..MAX_RESULTS + 5)
+            .map(|index| Command::new(format!("Refresh {index}"), Message::FetchData))
+            .chain([Command::new("Pause", Message::TogglePause)])
+            .collect();
+
+        let found = search(commands, "refresh");
+        assert_eq!(found.len(), MAX_RESULTS);
+        let expected: Vec<String> = (0..MAX_RESULTS)
+            .map(|index| format!("Refresh {index}"))
+            .collect();
+        assert_eq!(titles(&found), expected);
+
+        let ranked = ["Rules editor", "Refresh", "Reset"]
+            .map(|title| Command::new(title, Message::FetchData))
+            .to_vec();
+        assert_eq!(
+            titles(&search(ranked, "re")),
+            ["Refresh", "Reset", "Rules editor"]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::{ColumnKind, WatchItem, clock, source::DataSource};
+
+/// The computed columns a diagnostic report lists, worked out the way the table does.
+const DERIVED: [ColumnKind; 5] = [
+    ColumnKind::Spread,
+    ColumnKind::SpreadBps,
+    ColumnKind::Premium,
+    ColumnKind::ComputedChange24h,
+    ColumnKind::FundingRateAnnualized,
+];
+
+/// Builds a plain-text bundle describing one row, for pasting into bug reports. `origin` is
+/// where the row came from: the URL fetched, or "mock" for built-in data.
+///
+/// The app only talks to public endpoints, so there are no credentials to redact.
+pub fn diagnostic_bundle(
+    item: &WatchItem,
+    origin: &str,
+    source: DataSource,
+    now: DateTime<Utc>,
+) -> String {
+    let raw = serde_json::to_string_pretty(item).unwrap_or_else(|e| format!("<{e}>"));
+    let (base, quote) = item.base_quote().unwrap_or(("?", "?"));
+    let derived: String = DERIVED
+        .iter()
+        .map(|kind| {
+            let value = kind
+                .number(item)
+                .map_or("N/A".to_string(), |value| value.to_string());
+            format!("{}: {value}\n", kind.name().to_lowercase())
+        })
+        .collect();
+    let last_trade = item
+        .last_traded_at()
+        .map_or("N/A".to_string(), |at| clock::age(at, now));
+
+    format!(
+        "krader diagnostic report\n\
+         app version: {}\n\
+         source: {source}\n\
+         origin: {origin}\n\
+         captured at: {}\n\
+         \n\
+         raw ticker:\n\
+         {raw}\n\
+         \n\
+         derived:\n\
+         base/quote: {base}/{quote}\n\
+         {derived}\
+         last trade: {last_trade}\n\
+         crossed: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        item.is_crossed(),
+    )
+}
+
+/// The headings of `row_tsv`.
+const ROW_FIELDS: [&str; 6] = ["symbol", "markPrice", "bid", "ask", "change24h", "vol24h"];
+
+/// A row's key fields as two tab-separated lines, headings then values, ready to paste into a
+/// spreadsheet. Missing values are left blank.
+pub fn row_tsv(item: &WatchItem) -> String {
+    let number = |value: Option<f64>| value.map_or(String::new(), |value| value.to_string());
+    let values = [
+        item.symbol.clone().unwrap_or_default(),
+        number(item.mark_price),
+        number(item.bid),
+        number(item.ask),
+        number(item.change24h),
+        number(item.vol24h),
+    ];
+
+    format!("{}\n{}", ROW_FIELDS.join("\t"), values.join("\t"))
+}
+
+/// A row's key fields as a JSON object with the API's field names.
+pub fn row_json(item: &WatchItem) -> String {
+    json!({
+        "symbol": item.symbol,
+        "markPrice": item.mark_price,
+        "bid": item.bid,
+        "ask": item.ask,
+        "change24h": item.change24h,
+        "vol24h": item.vol24h,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_lists_the_computed_columns() {
+        let now = "2026-01-01T12:00:00Z".parse().unwrap();
+        let item = WatchItem {
+            symbol: Some("PF_XBTUSD".to_string()),
+            pair: Some("XBT:USD".to_string()),
+            bid: Some(99.0),
+            ask: Some(101.0),
+            last: Some(100.0),
+            mark_price: Some(100.0),
+            index_price: Some(80.0),
+            open24h: Some(80.0),
+            funding_rate: Some(0.0001),
+            last_time: Some("2026-01-01T11:57:00Z".to_string()),
+            ..Default::default()
+        };
+
+        let bundle = diagnostic_bundle(&item, "mock", DataSource::Futures, now);
+        let derived = bundle.split_once("derived:\n").unwrap().1;
+        let lines: Vec<&str> = derived.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "base/quote: XBT/USD",
+                "spread: 2",
+                "spread (bps): 200",
+                "premium over index: 0.25",
+                "24h change (computed): 25",
+                &format!(
+                    "annualized funding rate: {}",
+                    ColumnKind::FundingRateAnnualized.number(&item).unwrap()
+                ),
+                "last trade: 3m ago",
+                "crossed: false",
+            ]
+        );
+        assert!(bundle.contains("source: Futures\norigin: mock\n"));
+    }
+
+    #[test]
+    fn row_copies_leave_missing_values_blank() {
+        let item = WatchItem {
+            symbol: Some("PF_XBTUSD".to_string()),
+            mark_price: Some(104215.5),
+            bid: Some(104210.0),
+            change24h: Some(-1.25),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            row_tsv(&item),
+            "symbol\tmarkPrice\tbid\task\tchange24h\tvol24h\nPF_XBTUSD\t104215.5\t104210\t\t-1.25\t"
+        );
+        let json: serde_json::Value = serde_json::from_str(&row_json(&item)).unwrap();
+        assert_eq!(json["markPrice"], 104215.5);
+        assert!(json["ask"].is_null());
+    }
+}
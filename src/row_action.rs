@@ -0,0 +1,86 @@
+use std::fmt;
+use std::io;
+use std::process::Command;
+
+use krader::source::{self, DataSource};
+use serde::{Deserialize, Serialize};
+
+/// What clicking (or double-clicking) a row does.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowAction {
+    Nothing,
+    Select,
+    OpenDetail,
+    CopySymbol,
+    OpenWeb,
+}
+
+impl RowAction {
+    pub const ALL: [RowAction; 5] = [
+        RowAction::Nothing,
+        RowAction::Select,
+        RowAction::OpenDetail,
+        RowAction::CopySymbol,
+        RowAction::OpenWeb,
+    ];
+}
+
+impl fmt::Display for RowAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RowAction::Nothing => "Do nothing",
+            RowAction::Select => "Select",
+            RowAction::OpenDetail => "Open detail",
+            RowAction::CopySymbol => "Copy symbol",
+            RowAction::OpenWeb => "Open on Kraken",
+        })
+    }
+}
+
+/// The Kraken trading page for `symbol` on `source`.
+///
+/// Kraken Pro names spot markets by their common tickers, `btc-usd` rather than `XXBTZUSD`. A
+/// spot key that can't be split opens the market list instead.
+pub fn web_url(symbol: &str, source: DataSource) -> String {
+    match source {
+        DataSource::Futures => format!("https://futures.kraken.com/trade/futures/{symbol}"),
+        DataSource::Spot => match source::split_pair(symbol) {
+            Some((base, quote)) => {
+                let ticker = |asset: &str| match asset {
+                    "XBT" => "btc".to_string(),
+                    "XDG" => "doge".to_string(),
+                    asset => asset.to_lowercase(),
+                };
+                format!(
+                    "https://pro.kraken.com/app/trade/{}-{}",
+                    ticker(base),
+                    ticker(quote)
+                )
+            }
+            None => "https://pro.kraken.com/app/markets".to_string(),
+        },
+    }
+}
+
+/// Opens `url` in the default browser.
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let mut child = command.arg(url).spawn()?;
+    // Waiting off the UI thread reaps the opener once it exits instead of leaving a zombie.
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => log::warn!("Browser opener exited with {status}"),
+        Ok(_) => {}
+        Err(e) => log::warn!("Couldn't wait for the browser opener: {e}"),
+    });
+
+    Ok(())
+}
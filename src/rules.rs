@@ -0,0 +1,137 @@
+use std::fmt;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::{ColumnKind, WatchItem};
+
+/// A conditional formatting rule: color the `target` cell when `field` compares true against `value`.
+///
+/// Rules are evaluated in order and the last matching one wins.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub target: ColumnKind,
+    pub field: ColumnKind,
+    pub comparator: Comparator,
+    pub value: f64,
+    pub color: RuleColor,
+    /// Paint the cell background instead of the text.
+    pub background: bool,
+}
+
+impl Rule {
+    pub fn matches(&self, item: &WatchItem) -> bool {
+        self.field
+            .number(item)
+            .is_some_and(|field| self.comparator.holds(field, self.value))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} when {} {} {}",
+            self.target,
+            if self.background {
+                "background"
+            } else {
+                "text"
+            },
+            self.color,
+            self.field,
+            self.comparator,
+            self.value
+        )
+    }
+}
+
+/// The last rule in `rules` that applies to the `kind` cell of `item`.
+pub fn matching<'a>(rules: &'a [Rule], kind: ColumnKind, item: &WatchItem) -> Option<&'a Rule> {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.target == kind && rule.matches(item))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+impl Comparator {
+    pub const ALL: [Comparator; 5] = [
+        Comparator::Greater,
+        Comparator::GreaterOrEqual,
+        Comparator::Less,
+        Comparator::LessOrEqual,
+        Comparator::Equal,
+    ];
+
+    pub fn holds(&self, a: f64, b: f64) -> bool {
+        match self {
+            Comparator::Greater => a > b,
+            Comparator::GreaterOrEqual => a >= b,
+            Comparator::Less => a < b,
+            Comparator::LessOrEqual => a <= b,
+            Comparator::Equal => a == b,
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparator::Greater => ">",
+            Comparator::GreaterOrEqual => "≥",
+            Comparator::Less => "<",
+            Comparator::LessOrEqual => "≤",
+            Comparator::Equal => "=",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleColor {
+    Yellow,
+    Green,
+    Red,
+    Blue,
+    Purple,
+}
+
+impl RuleColor {
+    pub const ALL: [RuleColor; 5] = [
+        RuleColor::Yellow,
+        RuleColor::Green,
+        RuleColor::Red,
+        RuleColor::Blue,
+        RuleColor::Purple,
+    ];
+
+    pub fn color(&self) -> Color {
+        match self {
+            RuleColor::Yellow => Color::from_rgb(1.0, 0.85, 0.0),
+            RuleColor::Green => Color::from_rgb(0.0, 0.8, 0.3),
+            RuleColor::Red => Color::from_rgb(0.9, 0.2, 0.2),
+            RuleColor::Blue => Color::from_rgb(0.2, 0.5, 1.0),
+            RuleColor::Purple => Color::from_rgb(0.7, 0.3, 0.9),
+        }
+    }
+}
+
+impl fmt::Display for RuleColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RuleColor::Yellow => "yellow",
+            RuleColor::Green => "green",
+            RuleColor::Red => "red",
+            RuleColor::Blue => "blue",
+            RuleColor::Purple => "purple",
+        })
+    }
+}
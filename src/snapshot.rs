@@ -0,0 +1,131 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use iced::Rectangle;
+use iced::window::Screenshot;
+use iced::window::screenshot::CropError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("No directory available to save the snapshot to")]
+    NoDirectory,
+
+    #[error("Failed to write snapshot: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode snapshot: {0}")]
+    Png(#[from] png::EncodingError),
+
+    #[error("Failed to crop snapshot: {0}")]
+    Crop(#[from] CropError),
+}
+
+/// Cuts the part of `screenshot` under `bounds`, given in logical pixels like widget bounds.
+pub fn crop(screenshot: &Screenshot, bounds: Rectangle) -> Result<Screenshot, SnapshotError> {
+    let scale = screenshot.scale_factor as f32;
+    let x = ((bounds.x * scale).round().max(0.0) as u32).min(screenshot.size.width);
+    let y = ((bounds.y * scale).round().max(0.0) as u32).min(screenshot.size.height);
+    let width = ((bounds.width * scale).round().max(0.0) as u32).min(screenshot.size.width - x);
+    let height = ((bounds.height * scale).round().max(0.0) as u32).min(screenshot.size.height - y);
+
+    Ok(screenshot.crop(Rectangle {
+        x,
+        y,
+        width,
+        height,
+    })?)
+}
+
+/// Creates `{stem}.png` in `dir`, or `{stem}-2.png` and so on if that's taken, so snapshots
+/// taken within the same second don't overwrite each other.
+fn create(dir: &Path, stem: &str) -> io::Result<(File, PathBuf)> {
+    for attempt in 1.. {
+        let path = match attempt {
+            1 => dir.join(format!("{stem}.png")),
+            _ => dir.join(format!("{stem}-{attempt}.png")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("ran out of snapshot names")
+}
+
+/// Saves a screenshot taken at `now` as a PNG in the user's pictures directory.
+///
+/// iced's clipboard only carries text, so the image goes to a file instead.
+pub fn save(screenshot: &Screenshot, now: DateTime<Utc>) -> Result<PathBuf, SnapshotError> {
+    let dir = dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .ok_or(SnapshotError::NoDirectory)?;
+    let (file, path) = create(
+        &dir,
+        &format!(
+            "krader-{}",
+            now.with_timezone(&Local).format("%Y%m%d-%H%M%S")
+        ),
+    )?;
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        screenshot.size.width,
+        screenshot.size.height,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()?
+        .write_image_data(&screenshot.bytes)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crops_are_scaled_to_physical_pixels() {
+        // A 4x3 logical window at a scale of 2, each pixel holding its own index.
+        let size = iced::Size::new(8, 6);
+        let bytes: Vec<u8> = (0..size.width * size.height)
+            .flat_map(|index| [index as u8; 4])
+            .collect();
+        let screenshot = Screenshot::new(bytes, size, 2.0);
+
+        let cropped = crop(
+            &screenshot,
+            Rectangle::new((1.0, 1.0).into(), (2.0, 5.0).into()),
+        )
+        .unwrap();
+        assert_eq!(cropped.size, iced::Size::new(4, 4));
+        assert_eq!(cropped.bytes[0], 18);
+        assert!(
+            crop(
+                &screenshot,
+                Rectangle::new((4.0, 0.0).into(), (1.0, 1.0).into())
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn snapshots_in_the_same_second_get_their_own_files() {
+        let dir = std::env::temp_dir().join(format!("krader-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let (_, first) = create(&dir, "krader-20260101-120000").unwrap();
+        let (_, second) = create(&dir, "krader-20260101-120000").unwrap();
+        let (_, third) = create(&dir, "krader-20260101-120000").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first.file_name().unwrap(), "krader-20260101-120000.png");
+        assert_eq!(second.file_name().unwrap(), "krader-20260101-120000-2.png");
+        assert_eq!(third.file_name().unwrap(), "krader-20260101-120000-3.png");
+    }
+}
@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ColumnKind, WatchItem};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn toggled(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+/// Orders two rows by `kind`. Missing values always sort last, whatever the direction.
+pub fn compare(
+    kind: ColumnKind,
+    direction: SortDirection,
+    a: &WatchItem,
+    b: &WatchItem,
+) -> Ordering {
+    if kind.is_numeric() {
+        // NaN has no meaningful place among numbers, so it is treated as missing.
+        present_first(
+            kind.number(a).filter(|v| !v.is_nan()),
+            kind.number(b).filter(|v| !v.is_nan()),
+            direction,
+            f64::total_cmp,
+        )
+    } else {
+        present_first(kind.text(a), kind.text(b), direction, |a, b| a.cmp(b))
+    }
+}
+
+fn present_first<T>(
+    a: Option<T>,
+    b: Option<T>,
+    direction: SortDirection,
+    cmp: impl Fn(&T, &T) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match direction {
+            SortDirection::Ascending => cmp(&a, &b),
+            SortDirection::Descending => cmp(&b, &a),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last(symbol: &str, last: Option<f64>) -> WatchItem {
+        WatchItem {
+            symbol: Some(symbol.to_string()),
+            last,
+            ..Default::default()
+        }
+    }
+
+    fn sorted(kind: ColumnKind, direction: SortDirection, rows: &[WatchItem]) -> Vec<&str> {
+        let mut rows: Vec<&WatchItem> = rows.iter().collect();
+        rows.sort_by(|a, b| compare(kind, direction, a, b));
+        rows.into_iter()
+            .map(|row| row.symbol.as_deref().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn numbers_sort_numerically() {
+        let rows = [
+            last("A", Some(100.0)),
+            last("B", Some(99.0)),
+            last("C", Some(-1.0)),
+        ];
+
+        assert_eq!(
+            sorted(ColumnKind::Last, SortDirection::Ascending, &rows),
+            ["C", "B", "A"]
+        );
+        assert_eq!(
+            sorted(ColumnKind::Last, SortDirection::Descending, &rows),
+            ["A", "B", "C"]
+        );
+    }
+
+    #[test]
+    fn missing_and_nan_sort_last_either_way() {
+        let rows = [
+            last("none", None),
+            last("nan", Some(f64::NAN)),
+            last("two", Some(2.0)),
+            last("one", Some(1.0)),
+        ];
+
+        // Missing and NaN values compare equal, so the stable sort keeps their input order.
+        assert_eq!(
+            sorted(ColumnKind::Last, SortDirection::Ascending, &rows),
+            ["one", "two", "none", "nan"]
+        );
+        assert_eq!(
+            sorted(ColumnKind::Last, SortDirection::Descending, &rows),
+            ["two", "one", "none", "nan"]
+        );
+    }
+
+    #[test]
+    fn equal_values_keep_their_order() {
+        let rows = [
+            last("A", Some(5.0)),
+            last("B", Some(5.0)),
+            last("C", Some(5.0)),
+        ];
+
+        for direction in [SortDirection::Ascending, SortDirection::Descending] {
+            assert_eq!(sorted(ColumnKind::Last, direction, &rows), ["A", "B", "C"]);
+        }
+    }
+
+    #[test]
+    fn every_price_and_size_column_is_numeric() {
+        for kind in [
+            ColumnKind::MarkPrice,
+            ColumnKind::Last,
+            ColumnKind::Bid,
+            ColumnKind::Ask,
+            ColumnKind::BidSize,
+            ColumnKind::AskSize,
+            ColumnKind::LastSize,
+            ColumnKind::Vol24h,
+            ColumnKind::VolumeQuote,
+            ColumnKind::OpenInterest,
+            ColumnKind::IndexPrice,
+        ] {
+            assert!(kind.is_numeric(), "{kind} should sort numerically");
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::{DEFAULT_RETRY_AFTER, FetchError, WatchItem};
+
+/// Kraken's public spot ticker, which lists every pair when none is asked for.
+pub const SPOT_TICKER_URL: &str = "https://api.kraken.com/0/public/Ticker";
+
+/// Which of Kraken's markets the tickers come from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DataSource {
+    #[default]
+    Futures,
+    Spot,
+}
+
+impl DataSource {
+    pub const ALL: [DataSource; 2] = [DataSource::Futures, DataSource::Spot];
+}
+
+impl fmt::Display for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DataSource::Futures => "Futures",
+            DataSource::Spot => "Spot",
+        })
+    }
+}
+
+/// Quote currencies spot pairs end in, longest first so `USDT` isn't read as `USD`.
+const QUOTES: [&str; 12] = [
+    "USDT", "USDC", "USD", "EUR", "GBP", "JPY", "CAD", "AUD", "CHF", "XBT", "ETH", "DAI",
+];
+
+/// Splits a spot pair key into its base and quote currencies, like `XBT` and `USD` for both
+/// `XXBTZUSD` and `XBTUSD`.
+///
+/// Older assets carry a legacy `X` (crypto) or `Z` (fiat) prefix, giving eight-letter keys
+/// like `XETHZEUR`; other keys are split at a known quote currency.
+pub fn split_pair(key: &str) -> Option<(&str, &str)> {
+    let legacy = |c: u8| matches!(c, b'X' | b'Z');
+    let bytes = key.as_bytes();
+    if key.is_ascii() && bytes.len() == 8 && legacy(bytes[0]) && legacy(bytes[4]) {
+        return Some((&key[1..4], &key[5..8]));
+    }
+
+    QUOTES.iter().find_map(|quote| {
+        let base = key.strip_suffix(quote)?;
+        (!base.is_empty()).then_some((base, *quote))
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct SpotResponse {
+    #[serde(default)]
+    error: Vec<String>,
+    result: Option<HashMap<String, SpotTicker>>,
+}
+
+/// One pair of the spot ticker. Every figure is a string, and most are arrays of which the
+/// last entry covers the past 24 hours.
+#[derive(Deserialize, Debug)]
+struct SpotTicker {
+    /// Ask: price, whole lot volume, lot volume.
+    a: Vec<String>,
+    /// Bid: price, whole lot volume, lot volume.
+    b: Vec<String>,
+    /// Last trade: price, lot volume.
+    c: Vec<String>,
+    /// Volume: today, last 24 hours.
+    v: Vec<String>,
+    /// Volume weighted average price: today, last 24 hours.
+    p: Vec<String>,
+    l: Vec<String>,
+    h: Vec<String>,
+    /// Today's opening price.
+    o: String,
+}
+
+impl SpotTicker {
+    /// Maps the pair into the futures row shape, with `pair` as `BASE:QUOTE` like futures.
+    /// Fields spot has no notion of, like mark price and funding, stay empty and show as "N/A".
+    fn into_item(self, pair: String) -> WatchItem {
+        let number = |values: &[String], i: usize| values.get(i)?.parse::<f64>().ok();
+        let vol24h = number(&self.v, 1);
+
+        WatchItem {
+            pair: split_pair(&pair).map(|(base, quote)| format!("{base}:{quote}")),
+            symbol: Some(pair),
+            last: number(&self.c, 0),
+            last_size: number(&self.c, 1),
+            bid: number(&self.b, 0),
+            bid_size: number(&self.b, 2),
+            ask: number(&self.a, 0),
+            ask_size: number(&self.a, 2),
+            vol24h,
+            // Volume times its weighted average price is the turnover in the quote currency.
+            volume_quote: vol24h
+                .zip(number(&self.p, 1))
+                .map(|(volume, vwap)| volume * vwap),
+            high24h: number(&self.h, 1),
+            low24h: number(&self.l, 1),
+            open24h: self.o.parse().ok(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Extracts the pairs from a spot ticker response, sorted by name since the response is a map.
+///
+/// Spot reports errors, rate limits included, in the body rather than the status code.
+pub fn parse_spot(body: &str) -> Result<Vec<WatchItem>, FetchError> {
+    let resp: SpotResponse = serde_json::from_str(body)?;
+    if let Some(error) = resp.error.first() {
+        if error.starts_with("EAPI:Rate limit") {
+            return Err(FetchError::RateLimited {
+                retry_after: DEFAULT_RETRY_AFTER,
+            });
+        }
+        return Err(FetchError::Api(resp.error.join(", ")));
+    }
+
+    let mut pairs: Vec<(String, SpotTicker)> = resp
+        .result
+        .ok_or(FetchError::MissingTickers)?
+        .into_iter()
+        .collect();
+    if pairs.is_empty() {
+        return Err(FetchError::NoMarkets);
+    }
+    pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(pairs
+        .into_iter()
+        .map(|(pair, ticker)| ticker.into_item(pair))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed from a real `/0/public/Ticker` response.
+    const CAPTURED: &str = r#"{
+        "error": [],
+        "result": {
+            "XXBTZUSD": {
+                "a": ["104221.00000", "1", "1.000"],
+                "b": ["104210.10000", "2", "2.000"],
+                "c": ["104215.50000", "0.00150000"],
+                "v": ["812.40410982", "1893.44120000"],
+                "p": ["104100.12345", "104000.00000"],
+                "t": [21450, 48211],
+                "l": ["103100.00000", "102900.00000"],
+                "h": ["104800.00000", "104950.00000"],
+                "o": "103500.00000"
+            },
+            "XETHZUSD": {
+                "a": ["2500.10000", "5", "5.000"],
+                "b": ["2499.90000", "3", "3.000"],
+                "c": ["2500.00000", "0.10000000"],
+                "v": ["1000.0", "2000.0"],
+                "p": ["2490.0", "2480.0"],
+                "t": [100, 200],
+                "l": ["2400.0", "2390.0"],
+                "h": ["2550.0", "2560.0"],
+                "o": "2450.0"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn captured_response_parses() {
+        let tickers = parse_spot(CAPTURED).unwrap();
+        let symbols: Vec<Option<&str>> = tickers.iter().map(|t| t.symbol.as_deref()).collect();
+        assert_eq!(symbols, [Some("XETHZUSD"), Some("XXBTZUSD")]);
+
+        let xbt = &tickers[1];
+        assert_eq!(xbt.last, Some(104215.5));
+        assert_eq!(xbt.bid, Some(104210.1));
+        assert_eq!(xbt.ask_size, Some(1.0));
+        assert_eq!(xbt.vol24h, Some(1893.4412));
+        assert_eq!(xbt.volume_quote, Some(1893.4412 * 104000.0));
+        assert_eq!(xbt.open24h, Some(103500.0));
+        assert_eq!(xbt.mark_price, None);
+        assert_eq!(xbt.base_quote(), Some(("XBT", "USD")));
+        assert_eq!(tickers[0].base_quote(), Some(("ETH", "USD")));
+        assert_eq!(xbt.funding_rate, None);
+    }
+
+    #[test]
+    fn pair_keys_split() {
+        assert_eq!(split_pair("XXBTZUSD"), Some(("XBT", "USD")));
+        assert_eq!(split_pair("XETHXXBT"), Some(("ETH", "XBT")));
+        assert_eq!(split_pair("SOLUSD"), Some(("SOL", "USD")));
+        assert_eq!(split_pair("DOTUSDT"), Some(("DOT", "USDT")));
+        assert_eq!(split_pair("USDCEUR"), Some(("USDC", "EUR")));
+        assert_eq!(split_pair("USD"), None);
+        assert_eq!(split_pair("FOOBAR"), None);
+    }
+
+    #[test]
+    fn errors_in_the_body_fail() {
+        assert!(matches!(
+            parse_spot(r#"{"error":["EGeneral:Internal error"]}"#),
+            Err(FetchError::Api(_))
+        ));
+        assert!(matches!(
+            parse_spot(r#"{"error":["EAPI:Rate limit exceeded"]}"#),
+            Err(FetchError::RateLimited { .. })
+        ));
+        assert!(matches!(
+            parse_spot(r#"{"error":[],"result":{}}"#),
+            Err(FetchError::NoMarkets)
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use iced::mouse;
+use iced::widget::canvas::{Frame, Geometry, Path, Program, Stroke};
+use iced::{Point, Rectangle, Renderer, Size, Theme};
+
+use crate::{GREEN, GREY, RED, WatchItem};
+
+/// How many polls of mark price each market keeps.
+pub const SAMPLES: usize = 30;
+
+/// Recent mark prices by symbol, oldest first.
+pub type History = HashMap<String, VecDeque<f64>>;
+
+/// Adds each fetched market's mark price to its history, dropping markets no longer listed.
+/// Markets without a mark price keep what they had.
+pub fn record(history: &mut History, items: &[WatchItem]) {
+    let listed: HashSet<&str> = items
+        .iter()
+        .filter_map(|item| item.symbol.as_deref())
+        .collect();
+    history.retain(|symbol, _| listed.contains(symbol.as_str()));
+
+    for item in items {
+        let (Some(symbol), Some(price)) = (&item.symbol, item.mark_price) else {
+            continue;
+        };
+        if price.is_nan() {
+            continue;
+        }
+        let samples = history.entry(symbol.clone()).or_default();
+        if samples.len() == SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(price);
+    }
+}
+
+/// Spreads `samples` evenly across `size`, the lowest at the bottom edge and the highest at the
+/// top. A flat line runs through the middle.
+fn points(samples: &VecDeque<f64>, size: Size) -> Vec<Point> {
+    let (low, high) = samples
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), sample| {
+            (low.min(*sample), high.max(*sample))
+        });
+    let step = size.width / (samples.len().max(2) - 1) as f32;
+
+    samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let height = if high > low {
+                ((sample - low) / (high - low)) as f32
+            } else {
+                0.5
+            };
+            Point::new(index as f32 * step, size.height * (1.0 - height))
+        })
+        .collect()
+}
+
+/// A line of a market's recent mark prices, green when it ends higher than it started.
+pub struct Sparkline<'a> {
+    samples: &'a VecDeque<f64>,
+}
+
+impl<'a> Sparkline<'a> {
+    pub fn new(samples: &'a VecDeque<f64>) -> Self {
+        Self { samples }
+    }
+}
+
+impl<Message> Program<Message> for Sparkline<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) else {
+            return vec![];
+        };
+        if self.samples.len() < 2 {
+            return vec![];
+        }
+
+        let color = if last > first {
+            GREEN
+        } else if last < first {
+            RED
+        } else {
+            GREY
+        };
+        let path = Path::new(|builder| {
+            for (index, point) in points(self.samples, bounds.size()).into_iter().enumerate() {
+                if index == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.stroke(&path, Stroke::default().with_color(color).with_width(1.5));
+
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priced(symbol: &str, mark_price: Option<f64>) -> WatchItem {
+        WatchItem {
+            symbol: Some(symbol.to_string()),
+            mark_price,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_keeps_the_latest_samples() {
+        let mut history = History::new();
+        for poll in 0..SAMPLES + 5 {
+            record(
+                &mut history,
+                &[priced("PF_A", Some(poll as f64)), priced("PF_B", None)],
+            );
+        }
+
+        let samples = &history["PF_A"];
+        assert_eq!(samples.len(), SAMPLES);
+        assert_eq!(samples.front(), Some(&5.0));
+        assert_eq!(samples.back(), Some(&(SAMPLES as f64 + 4.0)));
+        assert!(!history.contains_key("PF_B"));
+
+        record(&mut history, &[priced("PF_C", Some(1.0))]);
+        assert!(!history.contains_key("PF_A"));
+        assert_eq!(history["PF_C"].len(), 1);
+    }
+
+    #[test]
+    fn points_span_the_cell() {
+        let size = Size::new(100.0, 20.0);
+
+        let line = points(&VecDeque::from([1.0, 3.0, 2.0]), size);
+        assert_eq!(
+            line,
+            [
+                Point::new(0.0, 20.0),
+                Point::new(50.0, 0.0),
+                Point::new(100.0, 10.0)
+            ]
+        );
+        let flat = points(&VecDeque::from([5.0, 5.0]), size);
+        assert_eq!(flat, [Point::new(0.0, 10.0), Point::new(100.0, 10.0)]);
+    }
+}
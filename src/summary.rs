@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::WatchItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteCurrency {
+    Usd,
+    Eur,
+    Gbp,
+    Xbt,
+    Eth,
+}
+
+impl QuoteCurrency {
+    pub const ALL: [QuoteCurrency; 5] = [
+        QuoteCurrency::Usd,
+        QuoteCurrency::Eur,
+        QuoteCurrency::Gbp,
+        QuoteCurrency::Xbt,
+        QuoteCurrency::Eth,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            QuoteCurrency::Usd => "USD",
+            QuoteCurrency::Eur => "EUR",
+            QuoteCurrency::Gbp => "GBP",
+            QuoteCurrency::Xbt => "XBT",
+            QuoteCurrency::Eth => "ETH",
+        }
+    }
+}
+
+impl fmt::Display for QuoteCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Aggregate activity across a set of rows, expressed in a single currency.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub volume_quote: f64,
+    pub open_interest_value: f64,
+    /// Rows left out because their quote currency could not be converted.
+    pub excluded: usize,
+}
+
+/// Conversion rates derived from the tickers themselves: the mark price of
+/// every `BASE:QUOTE` pair, keyed by `(base, quote)`.
+#[derive(Debug, Clone, Default)]
+pub struct Rates {
+    prices: HashMap<(String, String), f64>,
+}
+
+impl Rates {
+    pub fn from_tickers(tickers: &[WatchItem]) -> Self {
+        let mut prices = HashMap::new();
+
+        for item in tickers {
+            // Spot has no mark price, so its last trade stands in.
+            let (Some((base, quote)), Some(price)) =
+                (item.base_quote(), item.mark_price.or(item.last))
+            else {
+                continue;
+            };
+            if price > 0.0 {
+                prices
+                    .entry((base.to_string(), quote.to_string()))
+                    .or_insert(price);
+            }
+        }
+
+        Self { prices }
+    }
+
+    /// The factor turning an amount in `from` into an amount in `to`.
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        if let Some(price) = self.prices.get(&(from.to_string(), to.to_string())) {
+            return Some(*price);
+        }
+        self.prices
+            .get(&(to.to_string(), from.to_string()))
+            .map(|price| 1.0 / price)
+    }
+}
+
+pub fn summarize<'a>(
+    rows: impl IntoIterator<Item = &'a WatchItem>,
+    rates: &Rates,
+    currency: QuoteCurrency,
+) -> Summary {
+    let mut summary = Summary::default();
+
+    for row in rows {
+        let Some(rate) = row
+            .base_quote()
+            .and_then(|(_, quote)| rates.rate(quote, currency.code()))
+        else {
+            summary.excluded += 1;
+            continue;
+        };
+
+        summary.volume_quote += row.volume_quote.unwrap_or_default() * rate;
+        if let (Some(open_interest), Some(mark_price)) = (row.open_interest, row.mark_price) {
+            summary.open_interest_value += open_interest * mark_price * rate;
+        }
+    }
+
+    summary
+}
+
+/// Whether a market ranks among the biggest gainers or losers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankTag {
+    Gainer,
+    Loser,
+}
+
+/// The `n` markets up the most and the `n` down the most by `change24h`, keyed by symbol.
+///
+/// Only rising markets count as gainers and falling ones as losers, so a quiet day highlights
+/// fewer rows.
+pub fn movers<'a>(
+    rows: impl IntoIterator<Item = &'a WatchItem>,
+    n: usize,
+) -> HashMap<String, RankTag> {
+    let mut changes: Vec<(&str, f64)> = rows
+        .into_iter()
+        .filter_map(|row| Some((row.symbol.as_deref()?, row.change24h?)))
+        .filter(|(_, change)| change.is_finite())
+        .collect();
+    changes.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let gainers = changes
+        .iter()
+        .take(n)
+        .filter(|(_, change)| *change > 0.0)
+        .map(|(symbol, _)| (symbol.to_string(), RankTag::Gainer));
+    let losers = changes
+        .iter()
+        .rev()
+        .take(n)
+        .filter(|(_, change)| *change < 0.0)
+        .map(|(symbol, _)| (symbol.to_string(), RankTag::Loser));
+
+    gainers.chain(losers).collect()
+}
+
+/// The row that moved furthest from its 24h open, either way, with that move in percent.
+pub fn biggest_mover<'a>(
+    rows: impl IntoIterator<Item = &'a WatchItem>,
+) -> Option<(&'a WatchItem, f64)> {
+    rows.into_iter()
+        .filter_map(|row| Some((row, row.change_since_open()?)))
+        .filter(|(_, change)| change.is_finite())
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use iced::Theme;
+use serde::{Deserialize, Serialize};
+
+/// An explicit theme the user can pick instead of following the OS.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    Dracula,
+    Nord,
+    SolarizedLight,
+    SolarizedDark,
+}
+
+impl ThemeMode {
+    /// The OS light/dark preference, defaulting to dark when it can't be detected.
+    pub fn system() -> Self {
+        match dark_light::detect() {
+            dark_light::Mode::Light => ThemeMode::Light,
+            dark_light::Mode::Dark | dark_light::Mode::Default => ThemeMode::Dark,
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeMode::Dark => Theme::Dark,
+            ThemeMode::Light => Theme::Light,
+            ThemeMode::Dracula => Theme::Dracula,
+            ThemeMode::Nord => Theme::Nord,
+            ThemeMode::SolarizedLight => Theme::SolarizedLight,
+            ThemeMode::SolarizedDark => Theme::SolarizedDark,
+        }
+    }
+}
+
+/// The entries of the theme picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeChoice {
+    System,
+    Explicit(ThemeMode),
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 7] = [
+        ThemeChoice::System,
+        ThemeChoice::Explicit(ThemeMode::Dark),
+        ThemeChoice::Explicit(ThemeMode::Light),
+        ThemeChoice::Explicit(ThemeMode::Dracula),
+        ThemeChoice::Explicit(ThemeMode::Nord),
+        ThemeChoice::Explicit(ThemeMode::SolarizedLight),
+        ThemeChoice::Explicit(ThemeMode::SolarizedDark),
+    ];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ThemeChoice::System => "System",
+            ThemeChoice::Explicit(ThemeMode::Dark) => "Dark",
+            ThemeChoice::Explicit(ThemeMode::Light) => "Light",
+            ThemeChoice::Explicit(ThemeMode::Dracula) => "Dracula",
+            ThemeChoice::Explicit(ThemeMode::Nord) => "Nord",
+            ThemeChoice::Explicit(ThemeMode::SolarizedLight) => "Solarized Light",
+            ThemeChoice::Explicit(ThemeMode::SolarizedDark) => "Solarized Dark",
+        })
+    }
+}
+
+/// How tightly the table packs its rows.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Density {
+    #[default]
+    Normal,
+    /// Shorter rows and smaller text, to fit more markets on a small screen.
+    Compact,
+}
+
+impl Density {
+    pub fn row_height(self) -> f32 {
+        match self {
+            Density::Normal => 32.0,
+            Density::Compact => 22.0,
+        }
+    }
+
+    pub fn header_height(self) -> f32 {
+        match self {
+            Density::Normal => 24.0,
+            Density::Compact => 20.0,
+        }
+    }
+
+    /// The table's text size; normal is iced's default.
+    pub fn text_size(self) -> f32 {
+        match self {
+            Density::Normal => 16.0,
+            Density::Compact => 13.0,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// A user-configurable transform applied to a numeric cell before it is formatted.
+///
+/// Only a fixed, safe set of operations is supported, always applied in this order:
+///
+/// 1. `abs`: take the absolute value,
+/// 2. `multiply`: scale by a constant factor,
+/// 3. `add`: shift by a constant offset.
+///
+/// `suffix` is appended to the formatted value, so for example `F.RATE` with
+/// `multiply = 10000` and `suffix = " bps"` displays the funding rate in basis points.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Transform {
+    pub abs: bool,
+    pub multiply: f64,
+    pub add: f64,
+    pub suffix: String,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            abs: false,
+            multiply: 1.0,
+            add: 0.0,
+            suffix: String::new(),
+        }
+    }
+}
+
+impl Transform {
+    pub fn apply(&self, value: f64) -> f64 {
+        let value = if self.abs { value.abs() } else { value };
+
+        value * self.multiply + self.add
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
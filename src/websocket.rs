@@ -0,0 +1,499 @@
+//! A minimal WebSocket client (RFC 6455): the upgrade handshake, masked frames out, fragmented
+//! messages and control frames in. Enough for JSON feeds, nothing more.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::Url;
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, ReadHalf,
+    WriteHalf,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+
+/// Appended to the handshake key before hashing, fixed by the RFC.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The most a single message may hold, so a misbehaving server can't exhaust memory.
+const MAX_MESSAGE_SIZE: usize = 16 << 20;
+
+/// The most the handshake response headers may hold.
+const MAX_HEADER_SIZE: usize = 16 << 10;
+
+const CONTINUATION: u8 = 0x0;
+const TEXT: u8 = 0x1;
+const BINARY: u8 = 0x2;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xA;
+
+#[derive(Debug, Error)]
+pub enum WebSocketError {
+    #[error("Invalid WebSocket URL {0:?}")]
+    InvalidUrl(String),
+
+    #[error("WebSocket connection failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("WebSocket handshake failed: {0}")]
+    Handshake(String),
+
+    #[error("WebSocket protocol error: {0}")]
+    Protocol(&'static str),
+
+    #[error("WebSocket message of {0} bytes is too large")]
+    TooLarge(u64),
+}
+
+/// A message from the server. Pings are left to the caller to answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incoming {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    /// The server is closing the connection, with its status code if it gave one.
+    Close(Option<u16>),
+}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+type Connection = BufReader<Box<dyn Stream>>;
+
+/// The receiving half of a connection.
+pub struct Reader {
+    stream: ReadHalf<Connection>,
+    /// The opcode and data of a fragmented message still being received.
+    partial: Option<(u8, Vec<u8>)>,
+}
+
+/// The sending half of a connection.
+pub struct Writer {
+    stream: WriteHalf<Connection>,
+}
+
+/// Opens a `ws://` or `wss://` connection to `url`, split so one task can read while another
+/// writes.
+pub async fn connect(url: &str) -> Result<(Reader, Writer), WebSocketError> {
+    let invalid = || WebSocketError::InvalidUrl(url.to_string());
+    let parsed = Url::parse(url).map_err(|_| invalid())?;
+    let host = parsed.host_str().ok_or_else(invalid)?.to_string();
+    let secure = match parsed.scheme() {
+        "wss" => true,
+        "ws" => false,
+        _ => return Err(invalid()),
+    };
+    let port = parsed
+        .port_or_known_default()
+        .unwrap_or(if secure { 443 } else { 80 });
+
+    let tcp = TcpStream::connect((host.as_str(), port)).await?;
+    tcp.set_nodelay(true)?;
+    let stream: Box<dyn Stream> = if secure {
+        let name = ServerName::try_from(host.clone()).map_err(|_| invalid())?;
+        Box::new(tls_connector().connect(name, tcp).await?)
+    } else {
+        Box::new(tcp)
+    };
+    let mut stream = BufReader::new(stream);
+
+    let key = BASE64.encode(random::<16>());
+    let target = match parsed.query() {
+        Some(query) => format!("{}?{query}", parsed.path()),
+        None => parsed.path().to_string(),
+    };
+    let authority = match parsed.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host,
+    };
+    let request = format!(
+        "GET {target} HTTP/1.1\r\n\
+         Host: {authority}\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Key: {key}\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         User-Agent: krader/{}\r\n\
+         \r\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+    read_handshake(&mut stream, &key).await?;
+
+    let (reader, writer) = tokio::io::split(stream);
+    Ok((
+        Reader {
+            stream: reader,
+            partial: None,
+        },
+        Writer { stream: writer },
+    ))
+}
+
+/// Verifies TLS certificates against the Mozilla roots bundled at build time, like the HTTP
+/// client does.
+fn tls_connector() -> TlsConnector {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("the ring provider supports the default protocol versions")
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+    TlsConnector::from(Arc::new(config))
+}
+
+/// The `Sec-WebSocket-Accept` a server must answer `key` with.
+fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(ACCEPT_GUID.as_bytes());
+    BASE64.encode(hasher.finalize())
+}
+
+/// Reads the response headers, leaving anything after them buffered for the first frames.
+async fn read_handshake(stream: &mut Connection, key: &str) -> Result<(), WebSocketError> {
+    let mut status = None;
+    let mut accept = None;
+    let mut read = 0;
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(WebSocketError::Handshake(
+                "connection closed during the handshake".to_string(),
+            ));
+        }
+        read += line.len();
+        if read > MAX_HEADER_SIZE {
+            return Err(WebSocketError::Handshake(
+                "response headers are too large".to_string(),
+            ));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        match status {
+            None => status = Some(line.to_string()),
+            Some(_) => {
+                if let Some((name, value)) = line.split_once(':')
+                    && name.trim().eq_ignore_ascii_case("sec-websocket-accept")
+                {
+                    accept = Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+
+    let status = status.unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("101") {
+        return Err(WebSocketError::Handshake(format!(
+            "server answered {status:?}"
+        )));
+    }
+    if accept.as_deref() != Some(accept_key(key).as_str()) {
+        return Err(WebSocketError::Handshake(
+            "server sent the wrong accept key".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    getrandom::getrandom(&mut bytes).expect("the OS random number generator is available");
+    bytes
+}
+
+impl Reader {
+    /// Waits for the next message, joining fragmented ones back together.
+    pub async fn recv(&mut self) -> Result<Incoming, WebSocketError> {
+        loop {
+            let (fin, opcode, payload) = self.read_frame().await?;
+            match opcode {
+                CLOSE => {
+                    let code =
+                        (payload.len() >= 2).then(|| u16::from_be_bytes([payload[0], payload[1]]));
+                    return Ok(Incoming::Close(code));
+                }
+                PING => return Ok(Incoming::Ping(payload)),
+                PONG => return Ok(Incoming::Pong(payload)),
+                CONTINUATION => {
+                    let Some((_, data)) = &mut self.partial else {
+                        return Err(WebSocketError::Protocol(
+                            "continuation frame without a message to continue",
+                        ));
+                    };
+                    if data.len() + payload.len() > MAX_MESSAGE_SIZE {
+                        return Err(WebSocketError::TooLarge(
+                            (data.len() + payload.len()) as u64,
+                        ));
+                    }
+                    data.extend(payload);
+                }
+                TEXT | BINARY => {
+                    if self.partial.is_some() {
+                        return Err(WebSocketError::Protocol(
+                            "new message before the last one finished",
+                        ));
+                    }
+                    self.partial = Some((opcode, payload));
+                }
+                _ => return Err(WebSocketError::Protocol("unknown opcode")),
+            }
+
+            if fin && let Some((opcode, data)) = self.partial.take() {
+                return match opcode {
+                    TEXT => String::from_utf8(data)
+                        .map(Incoming::Text)
+                        .map_err(|_| WebSocketError::Protocol("text message is not UTF-8")),
+                    _ => Ok(Incoming::Binary(data)),
+                };
+            }
+        }
+    }
+
+    /// Reads one frame as its FIN bit, opcode and unmasked payload.
+    async fn read_frame(&mut self) -> Result<(bool, u8, Vec<u8>), WebSocketError> {
+        let mut head = [0; 2];
+        self.stream.read_exact(&mut head).await?;
+        if head[0] & 0x70 != 0 {
+            return Err(WebSocketError::Protocol("reserved bits set"));
+        }
+        let fin = head[0] & 0x80 != 0;
+        let opcode = head[0] & 0x0F;
+        let control = opcode & 0x8 != 0;
+
+        let length = match head[1] & 0x7F {
+            126 => u64::from(self.stream.read_u16().await?),
+            127 => self.stream.read_u64().await?,
+            length => u64::from(length),
+        };
+        if control && (!fin || length > 125) {
+            return Err(WebSocketError::Protocol(
+                "oversized or fragmented control frame",
+            ));
+        }
+        if length > MAX_MESSAGE_SIZE as u64 {
+            return Err(WebSocketError::TooLarge(length));
+        }
+        // Servers mustn't mask, but unmasking costs nothing and is harmless.
+        let mask = if head[1] & 0x80 != 0 {
+            let mut mask = [0; 4];
+            self.stream.read_exact(&mut mask).await?;
+            Some(mask)
+        } else {
+            None
+        };
+
+        let mut payload = vec![0; length as usize];
+        self.stream.read_exact(&mut payload).await?;
+        if let Some(mask) = mask {
+            apply_mask(&mut payload, mask);
+        }
+
+        Ok((fin, opcode, payload))
+    }
+}
+
+impl Writer {
+    pub async fn send_text(&mut self, text: &str) -> Result<(), WebSocketError> {
+        self.send(TEXT, text.as_bytes()).await
+    }
+
+    pub async fn send_ping(&mut self, payload: &[u8]) -> Result<(), WebSocketError> {
+        self.send(PING, payload).await
+    }
+
+    pub async fn send_pong(&mut self, payload: &[u8]) -> Result<(), WebSocketError> {
+        self.send(PONG, payload).await
+    }
+
+    /// Starts a clean close; the server answers with a close of its own.
+    pub async fn close(&mut self) -> Result<(), WebSocketError> {
+        // 1000: normal closure.
+        self.send(CLOSE, &1000u16.to_be_bytes()).await
+    }
+
+    /// Writes `payload` as one frame, masked as clients must.
+    async fn send(&mut self, opcode: u8, payload: &[u8]) -> Result<(), WebSocketError> {
+        let mut frame = Vec::with_capacity(payload.len() + 14);
+        frame.push(0x80 | opcode);
+        match payload.len() {
+            length @ ..=125 => frame.push(0x80 | length as u8),
+            length @ 126..=0xFFFF => {
+                frame.push(0x80 | 126);
+                frame.extend((length as u16).to_be_bytes());
+            }
+            length => {
+                frame.push(0x80 | 127);
+                frame.extend((length as u64).to_be_bytes());
+            }
+        }
+        let mask = random::<4>();
+        frame.extend(mask);
+        let start = frame.len();
+        frame.extend(payload);
+        apply_mask(&mut frame[start..], mask);
+
+        self.stream.write_all(&frame).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+}
+
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn accept_key_matches_the_rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    /// An unmasked server frame.
+    fn frame(fin: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![if fin { 0x80 } else { 0 } | opcode];
+        match payload.len() {
+            length @ ..=125 => frame.push(length as u8),
+            length => {
+                frame.push(126);
+                frame.extend((length as u16).to_be_bytes());
+            }
+        }
+        frame.extend(payload);
+        frame
+    }
+
+    /// Accepts one connection, answers the handshake, sends `frames` and returns what the
+    /// client sent after the handshake.
+    async fn serve(frames: Vec<u8>) -> (String, tokio::task::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws/v1", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = BufReader::new(socket);
+            let mut key = String::new();
+            loop {
+                let mut line = String::new();
+                socket.read_line(&mut line).await.unwrap();
+                if let Some(value) = line.strip_prefix("Sec-WebSocket-Key:") {
+                    key = value.trim().to_string();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                 Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept_key(&key)
+            );
+            // The first frames share a write with the handshake, as servers may send them.
+            let mut reply = response.into_bytes();
+            reply.extend(frames);
+            socket.write_all(&reply).await.unwrap();
+
+            let mut received = vec![];
+            let _ = socket.read_to_end(&mut received).await;
+            received
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn messages_are_reassembled_around_control_frames() {
+        let long = "x".repeat(300);
+        let frames = [
+            frame(true, TEXT, b"{\"event\":\"info\"}"),
+            frame(false, TEXT, b"hel"),
+            frame(true, PING, b"beat"),
+            frame(true, CONTINUATION, b"lo"),
+            frame(true, TEXT, long.as_bytes()),
+            frame(true, CLOSE, &1001u16.to_be_bytes()),
+        ]
+        .concat();
+        let (url, _server) = serve(frames).await;
+
+        let (mut reader, _writer) = connect(&url).await.unwrap();
+        assert_eq!(
+            reader.recv().await.unwrap(),
+            Incoming::Text("{\"event\":\"info\"}".to_string())
+        );
+        assert_eq!(
+            reader.recv().await.unwrap(),
+            Incoming::Ping(b"beat".to_vec())
+        );
+        assert_eq!(
+            reader.recv().await.unwrap(),
+            Incoming::Text("hello".to_string())
+        );
+        assert_eq!(reader.recv().await.unwrap(), Incoming::Text(long));
+        assert_eq!(reader.recv().await.unwrap(), Incoming::Close(Some(1001)));
+    }
+
+    #[tokio::test]
+    async fn sent_frames_are_masked() {
+        let (url, server) = serve(vec![]).await;
+
+        let (reader, mut writer) = connect(&url).await.unwrap();
+        writer.send_text("hi").await.unwrap();
+        drop((reader, writer));
+
+        let sent = server.await.unwrap();
+        assert_eq!(sent[0], 0x80 | TEXT);
+        assert_eq!(sent[1], 0x80 | 2);
+        let mut payload = sent[6..8].to_vec();
+        apply_mask(&mut payload, sent[2..6].try_into().unwrap());
+        assert_eq!(payload, b"hi");
+    }
+
+    #[tokio::test]
+    async fn a_wrong_accept_key_fails_the_handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 101 Switching Protocols\r\nSec-WebSocket-Accept: nope\r\n\r\n",
+                )
+                .await;
+        });
+
+        assert!(matches!(
+            connect(&url).await,
+            Err(WebSocketError::Handshake(_))
+        ));
+        assert!(matches!(
+            connect("https://example.com").await,
+            Err(WebSocketError::InvalidUrl(_))
+        ));
+    }
+}